@@ -1,16 +1,47 @@
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 use std::{env, fs};
 
-use arboard::{Clipboard, Error};
 use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-
-use crate::auth_core::crypto::Crypto;
-use crate::auth_core::entry::{Entries, Entry};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::auth_core::backup::{Backups, Mirror, MirrorStatus, Snapshot};
+use crate::auth_core::clipboard::CodeClipboard;
+use crate::auth_core::confirm::{ConfirmAction, Confirmation};
+use crate::auth_core::crypto::{Crypto, KEY_FILE};
+use crate::auth_core::entry::{
+    CODE_PLACEHOLDER, Entries, Entry, Secret, TrashedEntry, new_entry_id,
+};
+use crate::auth_core::history::History;
+use crate::auth_core::import::parse_json_export;
+use crate::auth_core::migration::parse_vault;
+use crate::auth_core::otpauth::{is_otpauth_uri, parse_otpauth};
+use crate::auth_core::search::{SearchMatch, search};
+use crate::auth_core::sort::{SortMode, sorted_indices};
+use crate::auth_core::sync::{
+    FileStamp, Mergeable, VaultLock, WATCH_INTERVAL, file_stamp, fingerprint, fingerprint_file,
+    is_unchanged, merge,
+};
+use crate::auth_core::tag::{collect_tags, format_tags, parse_tags};
+use crate::auth_core::totp::current_time;
 use crate::input::mouse;
 use crate::ui::file_browser::FileBrowser;
+use crate::ui::list_view::ListView;
+use crate::utils::config::{Config, RolloverAction};
+use crate::utils::hardening::{LockedBuffer, push_wiped};
+use crate::utils::time::unix_millis;
 use crate::{AuthError, AuthResult};
 
+/// Text to type before every entry is deleted.
+const DELETE_ALL_PHRASE: &str = "delete all";
+
+/// How long usage statistics from copying codes may stay unsaved, so that every copy does not
+/// rewrite the whole vault.
+const USAGE_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Name, secret, issuer, account, tags and copy template in the add and edit popups.
+const ENTRY_FIELDS: usize = 6;
+
 #[derive(PartialEq, Clone)]
 pub enum InputMode {
     Normal,
@@ -19,83 +50,197 @@ pub enum InputMode {
     Importing,
     Exporting,
     FileBrowser,
+    Restoring,
+    Locked,
+    Searching,
+    Trash,
 }
 
+#[allow(clippy::struct_excessive_bools)]
 pub struct App {
     pub should_quit: bool,
     pub entries: Vec<Entry>,
     pub selected: usize,
     pub input_mode: InputMode,
     pub new_entry_name: String,
-    pub new_entry_secret: String,
+    pub new_entry_secret: Secret,
+    pub new_entry_issuer: String,
+    pub new_entry_account: String,
+    pub new_entry_tags: String,
+    pub new_entry_template: Secret,
     pub edit_entry_name: String,
-    pub edit_entry_secret: String,
+    pub edit_entry_secret: Secret,
+    pub edit_entry_issuer: String,
+    pub edit_entry_account: String,
+    pub edit_entry_tags: String,
+    pub edit_entry_template: Secret,
+    /// Entry the edit popup was opened on, found again by id when it is submitted
+    editing_id: Option<u64>,
     pub input_field: usize,
     pub entries_path: PathBuf,
     pub copy_notification_time: Option<SystemTime>,
     pub path_input: String,
     pub error_message: Option<(String, SystemTime)>,
-    crypto: Crypto,
+    pub notification: Option<(String, SystemTime)>,
+    crypto: Option<Crypto>,
     pub file_browser: FileBrowser,
     pub file_operation: Option<InputMode>,
-    clipboard: Result<Clipboard, Error>,
+    pub clipboard: CodeClipboard,
+    backups: Backups,
+    pub snapshots: Vec<(Snapshot, Option<usize>)>,
+    pub snapshot_selected: usize,
+    pub mirror: Option<Mirror>,
+    pub mirror_status: Option<MirrorStatus>,
+    auth_dir: PathBuf,
+    base_entries: Vec<Entry>,
+    vault_fingerprint: Option<u64>,
+    vault_stamp: Option<FileStamp>,
+    last_vault_check: Instant,
+    idle_timeout: Option<Duration>,
+    last_activity: Instant,
+    pub privacy: bool,
+    revealed: Option<(usize, Instant)>,
+    reveal_duration: Duration,
+    pub focused: bool,
+    lock_on_focus_loss: bool,
+    rollover_threshold: u64,
+    rollover: RolloverAction,
+    /// Id of the entry a `wait` rollover deferred copying for, and when its next period starts
+    pending_copy: Option<(u64, u64)>,
+    /// When a copy first changed usage statistics that have not been saved since
+    usage_changed: Option<Instant>,
+    pub search_query: String,
+    /// Tag of the selected tab, `None` for the tab with every entry
+    pub tag_filter: Option<String>,
+    pending_g: bool,
+    pub confirmation: Option<Confirmation>,
+    history: History<Entries>,
+    pub trash: Vec<TrashedEntry>,
+    base_trash: Vec<TrashedEntry>,
+    pub trash_selected: usize,
+    trash_retention_secs: Option<u64>,
+    pub sort: SortMode,
 }
 
 impl App {
     /// # Errors
     pub fn new() -> AuthResult<Self> {
-        let auth_dir = Self::get_auth_directory()?;
+        Self::open(&Self::get_auth_directory())
+    }
+
+    /// Opens the vault in `auth_dir` rather than the one picked from the environment, creating
+    /// the directory if needed.
+    ///
+    /// # Errors
+    pub fn open(auth_dir: &Path) -> AuthResult<Self> {
+        fs::create_dir_all(auth_dir).map_err(|_| AuthError::CreateDirError)?;
         let entries_path = auth_dir.join("entries.toml");
-        let crypto = Self::initialize_crypto(&auth_dir)?;
-        let mut app = Self::create_initial_app(entries_path, crypto);
+        let crypto = Self::initialize_crypto(auth_dir)?;
+        let (config, config_error) = match Config::load(auth_dir) {
+            Ok(config) => (config, None),
+            Err(e) => (Config::default(), Some(e)),
+        };
+        let mut app = Self::create_initial_app(auth_dir, entries_path, crypto, &config);
 
         Self::try_load_entries(&mut app);
+        if let Some(e) = config_error {
+            app.add_error(&e.to_string());
+        }
         Ok(app)
     }
 
-    fn get_auth_directory() -> AuthResult<PathBuf> {
+    fn get_auth_directory() -> PathBuf {
         if let Ok(dir) = env::var("AUTH_ENTRIES_DIR") {
-            let auth_dir = PathBuf::from(dir);
-            fs::create_dir_all(&auth_dir).map_err(|_| AuthError::CreateDirError)?;
-            return Ok(auth_dir);
+            return PathBuf::from(dir);
         }
 
-        let auth_dir = dirs::data_dir().unwrap().join("auth");
-
-        fs::create_dir_all(&auth_dir).map_err(|_| AuthError::CreateDirError)?;
-
-        Ok(auth_dir)
+        dirs::data_dir().unwrap().join("auth")
     }
 
     fn initialize_crypto(auth_dir: &Path) -> AuthResult<Crypto> {
         Crypto::new(auth_dir).map_err(|_| AuthError::CryptoInitError)
     }
 
-    fn create_initial_app(entries_path: PathBuf, crypto: Crypto) -> App {
+    fn create_initial_app(
+        auth_dir: &Path,
+        entries_path: PathBuf,
+        crypto: Crypto,
+        config: &Config,
+    ) -> App {
         App {
             should_quit: false,
             entries: Vec::new(),
             selected: 0,
             input_mode: InputMode::Normal,
             new_entry_name: String::new(),
-            new_entry_secret: String::new(),
+            new_entry_secret: Secret::default(),
+            new_entry_issuer: String::new(),
+            new_entry_account: String::new(),
+            new_entry_tags: String::new(),
+            new_entry_template: Secret::default(),
             edit_entry_name: String::new(),
-            edit_entry_secret: String::new(),
+            edit_entry_secret: Secret::default(),
+            edit_entry_issuer: String::new(),
+            edit_entry_account: String::new(),
+            edit_entry_tags: String::new(),
+            edit_entry_template: Secret::default(),
+            editing_id: None,
             input_field: 0,
             entries_path,
             copy_notification_time: None,
             path_input: String::new(),
             error_message: None,
-            crypto,
+            notification: None,
+            crypto: Some(crypto),
             file_browser: FileBrowser::new(),
             file_operation: None,
-            clipboard: Clipboard::new(),
+            clipboard: CodeClipboard::new(&config.clipboard),
+            backups: Backups::new(auth_dir, config.backup.retention),
+            snapshots: Vec::new(),
+            snapshot_selected: 0,
+            mirror: config.backup.mirror_dir.as_ref().map(|dir| {
+                Mirror::new(
+                    Self::expand_path(&dir.to_string_lossy()),
+                    config.backup.reminder_days,
+                )
+            }),
+            mirror_status: None,
+            auth_dir: auth_dir.to_path_buf(),
+            base_entries: Vec::new(),
+            vault_fingerprint: None,
+            vault_stamp: None,
+            last_vault_check: Instant::now(),
+            idle_timeout: (config.lock.idle_timeout_secs > 0)
+                .then(|| Duration::from_secs(config.lock.idle_timeout_secs)),
+            last_activity: Instant::now(),
+            privacy: config.privacy.enabled,
+            revealed: None,
+            reveal_duration: Duration::from_secs(config.privacy.reveal_secs),
+            focused: true,
+            lock_on_focus_loss: config.lock.on_focus_loss,
+            rollover_threshold: config.clipboard.rollover_threshold_secs,
+            rollover: config.clipboard.rollover,
+            pending_copy: None,
+            usage_changed: None,
+            search_query: String::new(),
+            tag_filter: None,
+            pending_g: false,
+            confirmation: None,
+            history: History::default(),
+            trash: Vec::new(),
+            base_trash: Vec::new(),
+            trash_selected: 0,
+            trash_retention_secs: (config.trash.retention_days > 0)
+                .then(|| config.trash.retention_days.saturating_mul(86_400)),
+            sort: SortMode::Manual,
         }
     }
 
     fn try_load_entries(app: &mut App) {
-        if app.load_entries().is_err() {
-            app.show_error(&AuthError::ReadError.to_string());
+        match app.load_entries() {
+            Ok(()) => {}
+            Err(e @ AuthError::UnsupportedVersion(_)) => app.show_error(&e.to_string()),
+            Err(_) => app.show_error(&AuthError::ReadError.to_string()),
         }
     }
 
@@ -104,14 +249,201 @@ impl App {
             return Ok(());
         }
 
+        let _lock = self.lock_vault()?;
         let encrypted = self.read_encrypted_file()?;
         let decrypted = self.decrypt_data(&encrypted)?;
+        if decrypted.lock_failed() {
+            self.show_error(&AuthError::MemoryLockError.to_string());
+        }
         let contents = self.parse_utf8(&decrypted)?;
         self.parse_entries(&contents)?;
+        self.mark_synced(&encrypted);
 
         Ok(())
     }
 
+    fn lock_vault(&mut self) -> AuthResult<VaultLock> {
+        VaultLock::acquire(&self.auth_dir).inspect_err(|e| self.show_error(&e.to_string()))
+    }
+
+    /// Records the state shared with the file on disk, used to detect writes by other instances.
+    fn mark_synced(&mut self, encrypted: &[u8]) {
+        self.vault_fingerprint = Some(fingerprint(encrypted));
+        self.vault_stamp = file_stamp(&self.entries_path);
+        self.base_entries = self.entries.clone();
+        self.base_trash = self.trash.clone();
+    }
+
+    /// Stamp and fingerprint of the vault on disk when another process rewrote it since this one
+    /// last read or wrote it. The file is only hashed once its stamp changed.
+    fn external_change(&mut self) -> Option<(FileStamp, u64)> {
+        let stamp = file_stamp(&self.entries_path)?;
+        if Some(stamp) == self.vault_stamp {
+            return None;
+        }
+        let current = fingerprint_file(&self.entries_path)?;
+        if Some(current) == self.vault_fingerprint {
+            // Touched without being rewritten.
+            self.vault_stamp = Some(stamp);
+            return None;
+        }
+        Some((stamp, current))
+    }
+
+    /// Folds in changes another process saved since this one last loaded or saved the vault,
+    /// instead of overwriting them. A vault that disappeared is simply recreated on save.
+    fn merge_external_changes(&mut self) -> AuthResult<()> {
+        let Some((stamp, current)) = self.external_change() else {
+            return Ok(());
+        };
+
+        let disk = self.read_vault(&self.entries_path)?;
+        let selected = self.selected_id();
+        let previous = std::mem::take(&mut self.entries);
+        let previous_trash = std::mem::take(&mut self.trash);
+        self.entries = merge(&self.base_entries, &previous, &disk.entries);
+        self.trash = merge(&self.base_trash, &previous_trash, &disk.trash);
+        self.vault_fingerprint = Some(current);
+        self.vault_stamp = Some(stamp);
+        self.base_entries = disk.entries;
+        self.base_trash = disk.trash;
+        self.restore_selection(selected);
+
+        // Another instance saving only usage counts is not worth a notification.
+        if is_unchanged(&previous, &self.entries) && is_unchanged(&previous_trash, &self.trash) {
+            return Ok(());
+        }
+        self.notify(&describe_external_change(&previous, &self.entries));
+        Ok(())
+    }
+
+    fn selected_id(&self) -> Option<u64> {
+        self.entries.get(self.selected).map(|entry| entry.id)
+    }
+
+    /// Keeps the cursor on the same entry after the list was replaced, falling back to the
+    /// nearest valid position when that entry is gone.
+    fn restore_selection(&mut self, selected: Option<u64>) {
+        self.selected = selected
+            .and_then(|id| self.entries.iter().position(|e| e.id == id))
+            .unwrap_or_else(|| self.selected.min(self.entries.len().saturating_sub(1)));
+    }
+
+    /// Periodic housekeeping, called on every iteration of the main loop.
+    pub fn tick(&mut self) {
+        self.clipboard.clear_if_expired();
+        self.copy_pending();
+        self.check_idle();
+        self.watch_vault();
+        if let Some(mirror) = &mut self.mirror {
+            mirror.refresh();
+        }
+        if self
+            .usage_changed
+            .is_some_and(|since| since.elapsed() >= USAGE_SAVE_INTERVAL)
+        {
+            self.save_usage();
+        }
+    }
+
+    fn check_idle(&mut self) {
+        if self
+            .idle_timeout
+            .is_some_and(|timeout| self.last_activity.elapsed() >= timeout)
+        {
+            self.lock();
+        }
+    }
+
+    #[must_use]
+    pub fn is_locked(&self) -> bool {
+        self.crypto.is_none()
+    }
+
+    /// Key file that unlocking re-reads. Anyone who can read it can unlock the vault, so the lock
+    /// screen names it rather than suggesting a password guards the vault.
+    #[must_use]
+    pub fn key_path(&self) -> PathBuf {
+        self.auth_dir.join(KEY_FILE)
+    }
+
+    /// Drops the decrypted entries and the key, leaving only the lock screen until the key is
+    /// read again by [`App::unlock`].
+    pub fn lock(&mut self) {
+        if self.is_locked() {
+            return;
+        }
+
+        self.save_usage();
+        self.entries.clear();
+        self.base_entries.clear();
+        self.trash.clear();
+        self.base_trash.clear();
+        self.trash_selected = 0;
+        self.crypto = None;
+        self.pending_copy = None;
+        self.search_query.clear();
+        self.tag_filter = None;
+        self.confirmation = None;
+        self.history.clear();
+        self.new_entry_name.clear();
+        self.new_entry_secret.zeroize();
+        self.new_entry_issuer.clear();
+        self.new_entry_account.clear();
+        self.new_entry_tags.clear();
+        self.new_entry_template.zeroize();
+        self.edit_entry_name.clear();
+        self.edit_entry_secret.zeroize();
+        self.edit_entry_issuer.clear();
+        self.edit_entry_account.clear();
+        self.edit_entry_tags.clear();
+        self.edit_entry_template.zeroize();
+        self.input_field = 0;
+        self.snapshots.clear();
+        self.path_input.clear();
+        self.file_operation = None;
+        self.input_mode = InputMode::Locked;
+    }
+
+    pub fn unlock(&mut self) {
+        match Self::initialize_crypto(&self.auth_dir) {
+            Ok(crypto) => self.crypto = Some(crypto),
+            Err(e) => {
+                self.show_error(&e.to_string());
+                return;
+            }
+        }
+
+        self.selected = 0;
+        self.vault_fingerprint = None;
+        self.vault_stamp = None;
+        self.input_mode = InputMode::Normal;
+        self.last_activity = Instant::now();
+        Self::try_load_entries(self);
+    }
+
+    /// Reloads changes saved by other instances. Paused while a popup is open, so the entry it
+    /// works on stays put; whatever changed is merged on the next save or once it closes.
+    fn watch_vault(&mut self) {
+        let popup_open = !matches!(self.input_mode, InputMode::Normal | InputMode::Searching)
+            || self.confirmation.is_some();
+        if self.is_locked() || popup_open || self.last_vault_check.elapsed() < WATCH_INTERVAL {
+            return;
+        }
+        self.last_vault_check = Instant::now();
+
+        if self.external_change().is_none() {
+            return;
+        }
+
+        let Ok(_lock) = self.lock_vault() else {
+            return;
+        };
+        if let Err(e) = self.merge_external_changes() {
+            self.show_error(&e.to_string());
+        }
+    }
+
     fn read_encrypted_file(&mut self) -> AuthResult<Vec<u8>> {
         fs::read(&self.entries_path).map_err(|_| {
             self.show_error(&AuthError::ReadError.to_string());
@@ -119,88 +451,225 @@ impl App {
         })
     }
 
-    fn decrypt_data(&mut self, encrypted: &[u8]) -> AuthResult<Vec<u8>> {
-        self.crypto.decrypt(encrypted).map_err(|_| {
+    fn crypto(&self) -> AuthResult<&Crypto> {
+        self.crypto.as_ref().ok_or(AuthError::VaultLockedError)
+    }
+
+    fn decrypt_data(&mut self, encrypted: &[u8]) -> AuthResult<LockedBuffer> {
+        self.crypto()?.decrypt(encrypted).map_err(|_| {
             self.show_error(&AuthError::DecryptError.to_string());
             AuthError::DecryptError
         })
     }
 
-    fn parse_utf8(&mut self, data: &[u8]) -> AuthResult<String> {
-        String::from_utf8(data.to_vec()).map_err(|_| {
-            self.show_error(&AuthError::Utf8Error.to_string());
-            AuthError::Utf8Error
-        })
+    fn parse_utf8(&mut self, data: &[u8]) -> AuthResult<Secret> {
+        decode_utf8(data).inspect_err(|e| self.show_error(&e.to_string()))
     }
 
     fn parse_entries(&mut self, contents: &str) -> AuthResult<()> {
-        let entries = toml::from_str::<Entries>(contents).map_err(|_| {
-            self.show_error(&AuthError::ParseError.to_string());
-            AuthError::ParseError
-        })?;
+        let entries = parse_vault(contents).inspect_err(|e| self.show_error(&e.to_string()))?;
 
         self.entries = entries.entries;
+        self.trash = entries.trash;
+        self.sort = entries.sort;
         Ok(())
     }
 
     /// # Errors
     pub fn save_entries(&mut self) -> AuthResult<()> {
-        let contents = self.serialize_entries()?;
+        self.save_vault(true)
+    }
+
+    /// Writes the vault, snapshotting the previous one first unless only usage statistics
+    /// changed, so copying codes does not rotate real changes out of the backups.
+    fn save_vault(&mut self, snapshot: bool) -> AuthResult<()> {
+        self.crypto()?;
+        let _lock = self.lock_vault()?;
+        self.merge_external_changes()?;
+        self.purge_expired_trash();
+        let contents = self.serialize_entries(&self.vault_state())?;
         let encrypted = self.encrypt_contents(&contents)?;
+        if snapshot {
+            self.snapshot_entries();
+        }
         self.write_encrypted_file(&encrypted)?;
+        self.mark_synced(&encrypted);
+        self.mirror_entries(&encrypted);
+        self.usage_changed = None;
         Ok(())
     }
 
-    fn serialize_entries(&mut self) -> AuthResult<String> {
-        let entries = Entries {
+    /// Writes usage statistics that copies left unsaved, e.g. before quitting or locking.
+    pub fn save_usage(&mut self) {
+        if self.usage_changed.is_some() && self.save_vault(false).is_err() {
+            self.show_error(&AuthError::SaveError.to_string());
+        }
+    }
+
+    fn mirror_entries(&mut self, encrypted: &[u8]) {
+        let Some(mirror) = &mut self.mirror else {
+            return;
+        };
+
+        let now = SystemTime::now();
+        self.mirror_status = Some(match mirror.write(encrypted) {
+            Ok(()) => MirrorStatus::Succeeded(now),
+            Err(_) => MirrorStatus::Failed(now),
+        });
+    }
+
+    fn snapshot_entries(&mut self) {
+        if let Err(e) = self.backups.snapshot(&self.entries_path) {
+            self.show_error(&e.to_string());
+        }
+    }
+
+    fn read_vault(&self, path: &Path) -> AuthResult<Entries> {
+        let encrypted = fs::read(path).map_err(|_| AuthError::ReadError)?;
+        let decrypted = self
+            .crypto()?
+            .decrypt(&encrypted)
+            .map_err(|_| AuthError::DecryptError)?;
+        let contents = decode_utf8(&decrypted)?;
+        parse_vault(&contents)
+    }
+
+    /// Entries and trash as they are saved to the vault.
+    fn vault_state(&self) -> Entries {
+        Entries {
             entries: self.entries.clone(),
+            trash: self.trash.clone(),
+            sort: self.sort,
+            ..Entries::default()
+        }
+    }
+
+    /// Drops trashed entries older than the configured retention period.
+    fn purge_expired_trash(&mut self) {
+        let Some(retention) = self.trash_retention_secs else {
+            return;
         };
 
-        toml::to_string_pretty(&entries).map_err(|_| {
-            self.show_error(&AuthError::SerializeError.to_string());
-            AuthError::SerializeError
-        })
+        let now = unix_millis() / 1000;
+        self.trash
+            .retain(|trashed| now.saturating_sub(trashed.deleted_at) < retention);
+        self.trash_selected = self.trash_selected.min(self.trash.len().saturating_sub(1));
+    }
+
+    pub fn open_restore_view(&mut self) {
+        self.snapshots = self
+            .backups
+            .list()
+            .into_iter()
+            .map(|snapshot| {
+                let count = self
+                    .read_vault(&snapshot.path)
+                    .ok()
+                    .map(|vault| vault.entries.len());
+                (snapshot, count)
+            })
+            .collect();
+        self.snapshot_selected = 0;
+        self.input_mode = InputMode::Restoring;
+    }
+
+    /// Replaces the current entries, trash and sort order with the selected snapshot. The restore
+    /// is recorded like any other change, so `u` rolls it back.
+    pub fn restore_selected_snapshot(&mut self) {
+        let Some((snapshot, _)) = self.snapshots.get(self.snapshot_selected) else {
+            return;
+        };
+
+        match self.read_vault(&snapshot.path) {
+            Ok(vault) => {
+                let before = self.vault_state();
+                self.entries = vault.entries;
+                self.trash = vault.trash;
+                self.sort = vault.sort;
+                self.selected = 0;
+                self.trash_selected = 0;
+                self.commit_change("restoring the backup".to_string(), before);
+            }
+            Err(_) => self.show_error(&AuthError::RestoreError.to_string()),
+        }
+        self.close_restore_view();
+    }
+
+    fn close_restore_view(&mut self) {
+        self.snapshots.clear();
+        self.snapshot_selected = 0;
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn serialize_entries(&mut self, entries: &Entries) -> AuthResult<Secret> {
+        toml::to_string_pretty(entries)
+            .map(Zeroizing::new)
+            .map_err(|_| {
+                self.show_error(&AuthError::SerializeError.to_string());
+                AuthError::SerializeError
+            })
     }
 
     fn encrypt_contents(&mut self, contents: &str) -> AuthResult<Vec<u8>> {
-        self.crypto.encrypt(contents.as_bytes()).map_err(|_| {
+        self.crypto()?.encrypt(contents.as_bytes()).map_err(|_| {
             self.show_error(&AuthError::EncryptorError.to_string());
             AuthError::EncryptorError
         })
     }
 
     fn write_encrypted_file(&mut self, encrypted: &[u8]) -> AuthResult<()> {
-        fs::write(&self.entries_path, encrypted).map_err(|_| {
-            self.show_error(&AuthError::WriteError.to_string());
-            AuthError::WriteError
-        })
+        let tmp_path = self.entries_path.with_extension("toml.tmp");
+        fs::write(&tmp_path, encrypted)
+            .and_then(|()| fs::rename(&tmp_path, &self.entries_path))
+            .map_err(|_| {
+                self.show_error(&AuthError::WriteError.to_string());
+                AuthError::WriteError
+            })
     }
 
     pub fn add_entry(&mut self) {
+        if let Err(e) = expand_otpauth(
+            &mut self.new_entry_name,
+            &mut self.new_entry_secret,
+            &mut self.new_entry_issuer,
+            &mut self.new_entry_account,
+        ) {
+            self.show_error(&e.to_string());
+            return;
+        }
         if self.new_entry_name.is_empty() || self.new_entry_secret.is_empty() {
             self.show_error(&AuthError::EmptyEntryError.to_string());
             return;
         }
+        if !is_valid_template(&self.new_entry_template) {
+            self.show_error(&AuthError::TemplateError.to_string());
+            return;
+        }
+        let before = self.vault_state();
         self.entries.push(Entry {
+            id: new_entry_id(),
             name: self.new_entry_name.clone(),
+            issuer: self.new_entry_issuer.clone(),
+            account: self.new_entry_account.clone(),
+            tags: parse_tags(&self.new_entry_tags),
             secret: self.new_entry_secret.clone(),
+            template: self.new_entry_template.clone(),
+            ..Entry::default()
         });
-        if self.save_entries().is_err() {
-            self.show_error(&AuthError::SaveError.to_string());
-        }
+        self.commit_change(format!("add \"{}\"", self.new_entry_name), before);
     }
 
     pub fn delete_entry(&mut self) {
-        let Some(entries) = (!self.entries.is_empty()).then_some(&mut self.entries) else {
+        let Some(entry) = self.entries.get(self.selected) else {
             return;
         };
+        let label = format!("delete \"{}\"", entry.name);
+        let before = self.vault_state();
 
-        entries.remove(self.selected);
-        self.selected = self.selected.min(entries.len().saturating_sub(1));
-
-        if self.save_entries().is_err() {
-            self.show_error(&AuthError::SaveError.to_string());
-        }
+        let entry = self.entries.remove(self.selected);
+        self.move_to_trash(vec![entry]);
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+        self.commit_change(label, before);
     }
 
     pub fn delete_all_entries(&mut self) {
@@ -208,11 +677,98 @@ impl App {
             return;
         }
 
-        self.entries.clear();
+        let before = self.vault_state();
+        let entries = std::mem::take(&mut self.entries);
+        self.move_to_trash(entries);
         self.selected = 0;
+        self.commit_change("delete all".to_string(), before);
+    }
 
-        if self.save_entries().is_err() {
-            self.show_error(&AuthError::SaveError.to_string());
+    fn move_to_trash(&mut self, entries: Vec<Entry>) {
+        let deleted_at = unix_millis() / 1000;
+        self.trash.extend(
+            entries
+                .into_iter()
+                .map(|entry| TrashedEntry { entry, deleted_at }),
+        );
+    }
+
+    pub fn open_trash_view(&mut self) {
+        self.trash_selected = 0;
+        self.input_mode = InputMode::Trash;
+    }
+
+    fn close_trash_view(&mut self) {
+        self.trash_selected = 0;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Moves the selected trashed entry back to the end of the entry list.
+    pub fn restore_trashed_entry(&mut self) {
+        let Some(trashed) = self.trash.get(self.trash_selected) else {
+            return;
+        };
+
+        let label = format!("restore \"{}\"", trashed.entry.name);
+        let before = self.vault_state();
+        let trashed = self.trash.remove(self.trash_selected);
+        self.entries.push(trashed.entry);
+        self.selected = self.entries.len() - 1;
+        self.trash_selected = self.trash_selected.min(self.trash.len().saturating_sub(1));
+        self.commit_change(label, before);
+    }
+
+    fn purge_trashed_entry(&mut self) {
+        let Some(trashed) = self.trash.get(self.trash_selected) else {
+            return;
+        };
+
+        let label = format!("purge \"{}\"", trashed.entry.name);
+        let before = self.vault_state();
+        self.trash.remove(self.trash_selected);
+        self.trash_selected = self.trash_selected.min(self.trash.len().saturating_sub(1));
+        self.commit_change(label, before);
+    }
+
+    fn empty_trash(&mut self) {
+        if self.trash.is_empty() {
+            return;
+        }
+
+        let before = self.vault_state();
+        self.trash.clear();
+        self.trash_selected = 0;
+        self.commit_change("emptying the trash".to_string(), before);
+    }
+
+    fn handle_trash_mode(&mut self, key: KeyEvent) {
+        let len = self.trash.len();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.close_trash_view(),
+            KeyCode::Char('j') | KeyCode::Down if len > 0 => {
+                self.trash_selected = (self.trash_selected + 1) % len;
+            }
+            KeyCode::Char('k') | KeyCode::Up if len > 0 => {
+                self.trash_selected = self.trash_selected.checked_sub(1).unwrap_or(len - 1);
+            }
+            KeyCode::Enter | KeyCode::Char('r') => self.restore_trashed_entry(),
+            KeyCode::Char('x') if len > 0 => {
+                let entry = &self.trash[self.trash_selected].entry;
+                self.confirmation = Some(Confirmation::new(
+                    ConfirmAction::PurgeTrashEntry {
+                        id: entry.id,
+                        name: entry.name.clone(),
+                    },
+                    format!("Permanently delete \"{}\"?", entry.name),
+                ));
+            }
+            KeyCode::Char('X') if len > 0 => {
+                self.confirmation = Some(Confirmation::new(
+                    ConfirmAction::EmptyTrash,
+                    format!("Permanently delete all {len} entries in the trash?"),
+                ));
+            }
+            _ => {}
         }
     }
 
@@ -225,26 +781,138 @@ impl App {
     }
 
     fn try_copy_code(&mut self) {
+        let Ok(now) = current_time() else {
+            self.show_error(&AuthError::TotpError.to_string());
+            return;
+        };
         let entry = &self.entries[self.selected];
-        let (code, _) = entry.generate_totp_with_time();
+        let (code, remaining) = entry.generate_totp_at(now);
 
-        if let Ok(clipboard) = self.clipboard.as_mut() {
-            if clipboard.set_text(code).is_err() {
-                self.show_error(&AuthError::ClipboardError.to_string());
-                return;
+        if remaining > self.rollover_threshold {
+            self.pending_copy = None;
+            let text = entry.format_code(&code);
+            self.copy_code(self.selected, &text);
+            return;
+        }
+
+        match self.rollover {
+            RolloverAction::Next => {
+                let (next_code, _) = entry.generate_totp_at(now + remaining);
+                let text = entry.format_code(&next_code);
+                if self.copy_code(self.selected, &text) {
+                    self.notify(&format!("Copied next code, valid in {remaining}s"));
+                }
             }
-        } else {
-            self.show_error(&AuthError::ClipboardInitializeError.to_string());
+            RolloverAction::Wait => {
+                self.pending_copy = Some((entry.id, now + remaining));
+                self.notify(&format!("Copying new code in {remaining}s"));
+            }
+        }
+    }
+
+    /// Copies the code a `wait` rollover was deferred for, once its period has started.
+    fn copy_pending(&mut self) {
+        let Some((id, due)) = self.pending_copy else {
+            return;
+        };
+        let Ok(now) = current_time() else {
+            return;
+        };
+        if now < due {
             return;
         }
 
+        let found = self
+            .entries
+            .iter()
+            .position(|entry| entry.id == id)
+            .map(|index| {
+                let entry = &self.entries[index];
+                (index, entry.format_code(&entry.generate_totp_at(now).0))
+            });
+        self.pending_copy = None;
+        if let Some((index, code)) = found
+            && self.copy_code(index, &code)
+        {
+            self.notify("Copied new code");
+        }
+    }
+
+    fn copy_code(&mut self, index: usize, code: &str) -> bool {
+        if let Err(e) = self.clipboard.copy(code) {
+            self.show_error(&e.to_string());
+            return false;
+        }
+
         self.copy_notification_time = Some(SystemTime::now());
+        self.record_use(index);
+        true
+    }
+
+    /// Updates the usage statistics behind the recent and frequent sort modes. They are saved
+    /// with the next change, or by `tick` after `USAGE_SAVE_INTERVAL`.
+    fn record_use(&mut self, index: usize) {
+        let Some(entry) = self.entries.get_mut(index) else {
+            return;
+        };
+
+        entry.last_used = unix_millis() / 1000;
+        entry.use_count += 1;
+        self.usage_changed.get_or_insert_with(Instant::now);
+    }
+
+    /// Like usage statistics, the sort order is saved without a snapshot, so cycling through the
+    /// modes does not rotate real changes out of the backups.
+    pub fn cycle_sort_mode(&mut self) {
+        self.sort = self.sort.next();
+        if self.save_vault(false).is_err() {
+            self.show_error(&AuthError::SaveError.to_string());
+        }
+        self.notify(&format!("Sorted by {}", self.sort.label()));
+    }
+
+    /// Swaps the selected entry with its neighbour `offset` places away in manual order, as
+    /// shown in the selected tab.
+    pub fn move_entry(&mut self, offset: isize) {
+        if self.sort != SortMode::Manual {
+            self.show_error(&AuthError::SortedMoveError.to_string());
+            return;
+        }
+        let visible = self.visible_entries();
+        let Some(target) = visible
+            .iter()
+            .position(|m| m.index == self.selected)
+            .and_then(|position| position.checked_add_signed(offset))
+            .and_then(|target| visible.get(target))
+            .map(|m| m.index)
+        else {
+            return;
+        };
+
+        let label = format!("move \"{}\"", self.entries[self.selected].name);
+        let before = self.vault_state();
+        self.entries.swap(self.selected, target);
+        self.selected = target;
+        self.commit_change(label, before);
     }
 
     pub fn show_error(&mut self, message: &str) {
         self.error_message = Some((message.to_string(), SystemTime::now()));
     }
 
+    /// Shows `message` alongside an error that is still on screen, so that several problems
+    /// found at startup are all reported.
+    pub fn add_error(&mut self, message: &str) {
+        match &self.error_message {
+            Some((shown, _)) => self.show_error(&format!("{shown}, {message}")),
+            None => self.show_error(message),
+        }
+    }
+
+    pub fn notify(&mut self, message: &str) {
+        self.notification = Some((message.to_string(), SystemTime::now()));
+    }
+
     #[must_use]
     pub fn expand_path(path: &str) -> PathBuf {
         if path.starts_with('~') {
@@ -305,11 +973,15 @@ impl App {
 
     fn read_and_parse_entries(&mut self, path: &Path) -> Entries {
         if !self.validate_file_exists(path) {
-            return Entries { entries: vec![] };
+            return Entries::default();
         }
 
         let contents = self.read_file_contents(path);
-        self.parse_toml_contents(contents.as_str())
+        if path.extension().is_some_and(|ext| ext == "json") {
+            self.parse_json_contents(&contents)
+        } else {
+            self.parse_toml_contents(&contents)
+        }
     }
 
     fn validate_file_exists(&mut self, path: &Path) -> bool {
@@ -320,30 +992,66 @@ impl App {
         true
     }
 
-    fn read_file_contents(&mut self, path: &Path) -> String {
-        fs::read_to_string(path).unwrap_or_else(|_| {
-            self.show_error(&AuthError::ReadError.to_string());
-            String::new()
-        })
+    fn read_file_contents(&mut self, path: &Path) -> Secret {
+        fs::read_to_string(path).map_or_else(
+            |_| {
+                self.show_error(&AuthError::ReadError.to_string());
+                Secret::default()
+            },
+            Zeroizing::new,
+        )
     }
 
     fn parse_toml_contents(&mut self, contents: &str) -> Entries {
         if contents.is_empty() {
-            return Entries { entries: vec![] };
+            return Entries::default();
         }
 
-        toml::from_str(contents).unwrap_or_else(|_| {
-            self.show_error(&AuthError::ParseError.to_string());
-            Entries { entries: vec![] }
+        parse_vault(contents).unwrap_or_else(|e| {
+            self.show_error(&e.to_string());
+            Entries::default()
         })
     }
 
-    fn merge_and_save_entries(&mut self, entries: Entries) {
-        self.entries.extend(entries.entries);
+    fn parse_json_contents(&mut self, contents: &str) -> Entries {
+        if contents.is_empty() {
+            return Entries::default();
+        }
 
-        if self.save_entries().is_err() {
-            self.show_error(&AuthError::SaveError.to_string());
+        match parse_json_export(contents) {
+            Ok(imported) => {
+                if imported.skipped > 0 {
+                    self.show_error(&format!(
+                        "Skipped {} entries with unsupported settings",
+                        imported.skipped
+                    ));
+                }
+                Entries {
+                    entries: imported.entries,
+                    ..Entries::default()
+                }
+            }
+            Err(e) => {
+                self.show_error(&e.to_string());
+                Entries::default()
+            }
+        }
+    }
+
+    fn merge_and_save_entries(&mut self, mut entries: Entries) {
+        if entries.entries.is_empty() {
+            return;
         }
+
+        // Imported entries are new to this vault, even when the file is an export of it.
+        for entry in &mut entries.entries {
+            entry.id = new_entry_id();
+        }
+
+        let label = format!("import of {} entries", entries.entries.len());
+        let before = self.vault_state();
+        self.entries.extend(entries.entries);
+        self.commit_change(label, before);
     }
 
     fn validate_import_path(&mut self, path: &Path) {
@@ -357,8 +1065,11 @@ impl App {
             return;
         }
 
-        if path.extension().is_none_or(|ext| ext != "toml") {
-            self.show_error(&AuthError::TomlExtError.to_string());
+        if path
+            .extension()
+            .is_none_or(|ext| ext != "toml" && ext != "json")
+        {
+            self.show_error(&AuthError::ImportExtError.to_string());
         }
     }
 
@@ -375,10 +1086,24 @@ impl App {
         }
 
         let path = self.get_validated_export_path();
-        let contents = self.serialize_entries()?;
-        self.write_export_file(&path, &contents)?;
+        if path.exists() {
+            self.confirmation = Some(Confirmation::new(
+                ConfirmAction::OverwriteExport(path.clone()),
+                format!("Overwrite {}?", path.display()),
+            ));
+            return Ok(());
+        }
 
-        Ok(())
+        self.write_export(&path)
+    }
+
+    fn write_export(&mut self, path: &Path) -> AuthResult<()> {
+        let entries = Entries {
+            entries: self.entries.clone(),
+            ..Entries::default()
+        };
+        let contents = self.serialize_entries(&entries)?;
+        self.write_export_file(path, &contents)
     }
 
     fn get_validated_export_path(&mut self) -> PathBuf {
@@ -402,29 +1127,55 @@ impl App {
         })
     }
 
+    /// Handles an input event, with `list` telling where the entry list was last drawn.
+    ///
     /// # Errors
-    pub fn handle_events(&mut self, event: &Event) -> AuthResult<()> {
+    pub fn handle_events(&mut self, event: &Event, list: &ListView) -> AuthResult<()> {
+        if matches!(event, Event::Key(_) | Event::Mouse(_)) {
+            self.last_activity = Instant::now();
+        }
+
         match event {
-            Event::Key(key) => self.handle_key_event(*key),
+            Event::Key(key) => self.handle_key_event(*key, list.height),
             Event::Mouse(mouse) => {
-                mouse::handle_mouse_event(self, *mouse);
+                mouse::handle_mouse_event(self, *mouse, list);
+                Ok(())
+            }
+            Event::FocusLost => {
+                self.handle_focus_lost();
+                Ok(())
+            }
+            Event::FocusGained => {
+                self.focused = true;
                 Ok(())
             }
             _ => Ok(()),
         }
     }
 
-    fn handle_key_event(&mut self, key: KeyEvent) -> AuthResult<()> {
+    fn handle_focus_lost(&mut self) {
+        self.focused = false;
+        self.revealed = None;
+        if self.lock_on_focus_loss {
+            self.lock();
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent, page: usize) -> AuthResult<()> {
         if key.kind != KeyEventKind::Press {
             return Ok(());
         }
         if self.check_control_quit(key) {
             return Ok(());
         }
+        if self.confirmation.is_some() {
+            self.handle_confirm_mode(key);
+            return Ok(());
+        }
 
         match self.input_mode {
             InputMode::Normal => {
-                self.handle_normal_mode(key);
+                self.handle_normal_mode(key, page);
                 Ok(())
             }
             InputMode::Adding | InputMode::Editing => {
@@ -436,18 +1187,46 @@ impl App {
                 self.handle_file_browser_mode(key);
                 Ok(())
             }
+            InputMode::Restoring => {
+                self.handle_restore_mode(key);
+                Ok(())
+            }
+            InputMode::Locked => {
+                self.handle_locked_mode(key);
+                Ok(())
+            }
+            InputMode::Searching => {
+                self.handle_search_mode(key, page);
+                Ok(())
+            }
+            InputMode::Trash => {
+                self.handle_trash_mode(key);
+                Ok(())
+            }
         }
     }
 
-    fn handle_normal_mode(&mut self, key: KeyEvent) {
+    fn handle_normal_mode(&mut self, key: KeyEvent, page: usize) {
+        if std::mem::take(&mut self.pending_g) && key.code == KeyCode::Char('g') {
+            self.first_entry();
+            return;
+        }
+
         match key.code {
             KeyCode::Char('q') => self.should_quit = true,
             KeyCode::Char('j') | KeyCode::Down => self.next_entry(),
             KeyCode::Char('k') | KeyCode::Up => self.previous_entry(),
+            KeyCode::PageDown => self.next_page(page),
+            KeyCode::PageUp => self.previous_page(page),
+            KeyCode::Home => self.first_entry(),
+            KeyCode::End | KeyCode::Char('G') => self.last_entry(),
+            KeyCode::Char('g') => self.pending_g = true,
             KeyCode::Char('E') => self.start_editing(),
-            KeyCode::Char('a') => self.input_mode = InputMode::Adding,
-            KeyCode::Char('D') => self.delete_all_entries(),
-            KeyCode::Char('d') => self.delete_entry(),
+            KeyCode::Char('a') => self.start_adding(),
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => self.redo(),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('D') => self.confirm_delete_all(),
+            KeyCode::Char('d') => self.confirm_delete_entry(),
             KeyCode::Char('i') => {
                 self.file_operation = Some(InputMode::Importing);
                 self.file_browser.reset();
@@ -458,11 +1237,331 @@ impl App {
                 self.file_browser.reset();
                 self.input_mode = InputMode::FileBrowser;
             }
+            KeyCode::Char('b') => self.open_restore_view(),
+            KeyCode::Char('T') => self.open_trash_view(),
+            KeyCode::Char('J') => self.move_entry(1),
+            KeyCode::Char('K') => self.move_entry(-1),
+            KeyCode::Char('o') => self.cycle_sort_mode(),
+            KeyCode::Char('L') => self.lock(),
+            KeyCode::Char('p') => self.toggle_privacy(),
+            KeyCode::Char('v') => self.reveal_current_code(),
+            KeyCode::Char('/') => self.start_search(""),
+            KeyCode::Tab => self.cycle_tag_tab(true),
+            KeyCode::BackTab => self.cycle_tag_tab(false),
             KeyCode::Enter => self.copy_current_code(),
             _ => {}
         }
     }
 
+    /// Opens the add popup, with the tag of the selected tab filled in so the new entry shows up
+    /// there.
+    fn start_adding(&mut self) {
+        if let Some(tag) = self.active_tag().map(str::to_string) {
+            self.new_entry_tags = tag;
+        }
+        self.input_mode = InputMode::Adding;
+    }
+
+    /// Saves the mutation described by `label` made since the vault was `before`, and remembers
+    /// it for undo once it is on disk. Mutations that changed nothing are neither saved nor
+    /// remembered.
+    fn commit_change(&mut self, label: String, before: Entries) {
+        let after = self.vault_state();
+        if is_unchanged(&before.entries, &after.entries)
+            && is_unchanged(&before.trash, &after.trash)
+            && before.sort == after.sort
+        {
+            return;
+        }
+        if self.save_entries().is_err() {
+            self.show_error(&AuthError::SaveError.to_string());
+            return;
+        }
+        self.history.record(label, before, after);
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(change) = self.history.undo() {
+            self.apply_history(
+                &change.after,
+                &change.before,
+                &format!("Undid {}", change.label),
+            );
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(change) = self.history.redo() {
+            self.apply_history(
+                &change.before,
+                &change.after,
+                &format!("Redid {}", change.label),
+            );
+        }
+    }
+
+    /// Replays the change from `from` to `to` on top of the current vault, so anything changed
+    /// since, here or by another instance, is kept.
+    fn apply_history(&mut self, from: &Entries, to: &Entries, message: &str) {
+        let selected = self.selected_id();
+        self.entries = merge(&from.entries, &to.entries, &self.entries);
+        self.trash = merge(&from.trash, &to.trash, &self.trash);
+        if from.sort != to.sort {
+            self.sort = to.sort;
+        }
+        self.trash_selected = self.trash_selected.min(self.trash.len().saturating_sub(1));
+        self.restore_selection(selected);
+        if self.save_entries().is_ok() {
+            self.notify(message);
+        } else {
+            self.show_error(&AuthError::SaveError.to_string());
+        }
+    }
+
+    fn handle_confirm_mode(&mut self, key: KeyEvent) {
+        let Some(confirmation) = self.confirmation.as_mut() else {
+            return;
+        };
+
+        match (key.code, confirmation.phrase.is_some()) {
+            (KeyCode::Esc, _) | (KeyCode::Char('n' | 'q'), false) => self.confirmation = None,
+            (KeyCode::Enter | KeyCode::Char('y'), false) | (KeyCode::Enter, true) => {
+                if confirmation.is_satisfied() {
+                    let action = confirmation.action.clone();
+                    self.confirmation = None;
+                    self.run_confirmed(action);
+                } else {
+                    self.show_error(&AuthError::ConfirmationError.to_string());
+                }
+            }
+            (KeyCode::Char(c), true) => confirmation.input.push(c),
+            (KeyCode::Backspace, true) => {
+                confirmation.input.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn run_confirmed(&mut self, action: ConfirmAction) {
+        match action {
+            ConfirmAction::DeleteEntry { id, name } => {
+                let found = self
+                    .entries
+                    .iter()
+                    .position(|e| e.id == id && e.name == name);
+                match found {
+                    Some(index) => {
+                        self.selected = index;
+                        self.delete_entry();
+                    }
+                    None => self.show_error(&AuthError::EntryChangedError.to_string()),
+                }
+            }
+            ConfirmAction::DeleteAll => self.delete_all_entries(),
+            ConfirmAction::PurgeTrashEntry { id, name } => {
+                let found = self
+                    .trash
+                    .iter()
+                    .position(|t| t.entry.id == id && t.entry.name == name);
+                match found {
+                    Some(index) => {
+                        self.trash_selected = index;
+                        self.purge_trashed_entry();
+                    }
+                    None => self.show_error(&AuthError::EntryChangedError.to_string()),
+                }
+            }
+            ConfirmAction::EmptyTrash => self.empty_trash(),
+            ConfirmAction::OverwriteExport(path) => {
+                if let Err(e) = self.write_export(&path) {
+                    self.show_error(&e.to_string());
+                }
+            }
+        }
+    }
+
+    /// Asks for the selected entry's name before deleting it.
+    pub fn confirm_delete_entry(&mut self) {
+        let Some(entry) = self.entries.get(self.selected) else {
+            return;
+        };
+
+        self.confirmation = Some(
+            Confirmation::new(
+                ConfirmAction::DeleteEntry {
+                    id: entry.id,
+                    name: entry.name.clone(),
+                },
+                format!("Delete \"{}\"?", entry.name),
+            )
+            .with_phrase(&entry.name),
+        );
+    }
+
+    pub fn confirm_delete_all(&mut self) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        self.confirmation = Some(
+            Confirmation::new(
+                ConfirmAction::DeleteAll,
+                format!("Delete all {} entries?", self.entries.len()),
+            )
+            .with_phrase(DELETE_ALL_PHRASE),
+        );
+    }
+
+    fn handle_search_mode(&mut self, key: KeyEvent, page: usize) {
+        match key.code {
+            KeyCode::Esc => self.end_search(),
+            KeyCode::Enter => {
+                if !self.visible_entries().is_empty() {
+                    self.copy_current_code();
+                }
+                self.end_search();
+            }
+            KeyCode::Down => self.next_entry(),
+            KeyCode::Up => self.previous_entry(),
+            KeyCode::PageDown => self.next_page(page),
+            KeyCode::PageUp => self.previous_page(page),
+            KeyCode::Char(c) => {
+                self.search_query.push(c);
+                self.select_top_match();
+            }
+            KeyCode::Backspace => {
+                self.search_query.pop();
+                self.select_top_match();
+            }
+            _ => {}
+        }
+    }
+
+    /// Enters search mode with `query` already typed, e.g. from the command line.
+    pub fn start_search(&mut self, query: &str) {
+        if self.is_locked() {
+            return;
+        }
+
+        self.input_mode = InputMode::Searching;
+        self.search_query = query.to_string();
+        self.select_top_match();
+    }
+
+    /// Leaves search mode, keeping the cursor on the entry that was selected in the results.
+    fn end_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.search_query.clear();
+    }
+
+    fn select_top_match(&mut self) {
+        if let Some(top) = self.visible_entries().first() {
+            self.selected = top.index;
+        }
+    }
+
+    /// Entries currently shown in the main list: every entry in the selected tab, or the matches
+    /// of an ongoing search, best first.
+    #[must_use]
+    pub fn visible_entries(&self) -> Vec<SearchMatch> {
+        self.entries_in_tab(self.active_tag())
+    }
+
+    /// Like [`Self::visible_entries`], for a tab already looked up with [`Self::active_tag`].
+    #[must_use]
+    pub fn entries_in_tab(&self, tag: Option<&str>) -> Vec<SearchMatch> {
+        let order: Vec<usize> = sorted_indices(&self.entries, self.sort)
+            .into_iter()
+            .filter(|&i| tag.is_none_or(|tag| self.entries[i].has_tag(tag)))
+            .collect();
+        search(&self.entries, &order, &self.search_query)
+    }
+
+    /// Tag of the selected tab, ignoring a tab whose tag was removed from every entry.
+    #[must_use]
+    pub fn active_tag(&self) -> Option<&str> {
+        self.tag_filter
+            .as_deref()
+            .filter(|&tag| self.entries.iter().any(|e| e.has_tag(tag)))
+    }
+
+    /// Selects the next or previous tab, where the tab before the first tag shows every entry.
+    pub fn cycle_tag_tab(&mut self, forward: bool) {
+        let tags = collect_tags(&self.entries);
+        if tags.is_empty() {
+            return;
+        }
+
+        let tab_count = tags.len() + 1;
+        let current = self
+            .active_tag()
+            .and_then(|tag| tags.iter().position(|t| t.eq_ignore_ascii_case(tag)))
+            .map_or(0, |i| i + 1);
+        let next = if forward {
+            (current + 1) % tab_count
+        } else {
+            (current + tab_count - 1) % tab_count
+        };
+        self.tag_filter = next.checked_sub(1).map(|i| tags[i].clone());
+
+        if !self
+            .visible_entries()
+            .iter()
+            .any(|m| m.index == self.selected)
+        {
+            self.select_top_match();
+        }
+    }
+
+    fn handle_restore_mode(&mut self, key: KeyEvent) {
+        let len = self.snapshots.len();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.close_restore_view(),
+            KeyCode::Char('j') | KeyCode::Down if len > 0 => {
+                self.snapshot_selected = (self.snapshot_selected + 1) % len;
+            }
+            KeyCode::Char('k') | KeyCode::Up if len > 0 => {
+                self.snapshot_selected = self.snapshot_selected.checked_sub(1).unwrap_or(len - 1);
+            }
+            KeyCode::Enter => self.restore_selected_snapshot(),
+            _ => {}
+        }
+    }
+
+    pub fn toggle_privacy(&mut self) {
+        self.privacy = !self.privacy;
+        self.revealed = None;
+    }
+
+    /// Shows the selected entry's code for the configured reveal duration in privacy mode.
+    pub fn reveal_current_code(&mut self) {
+        if self.privacy && !self.entries.is_empty() {
+            self.revealed = Some((self.selected, Instant::now()));
+        }
+    }
+
+    #[must_use]
+    pub fn is_code_visible(&self, index: usize) -> bool {
+        if !self.focused {
+            return false;
+        }
+
+        !self.privacy
+            || self.revealed.is_some_and(|(revealed, time)| {
+                revealed == index
+                    && revealed == self.selected
+                    && time.elapsed() < self.reveal_duration
+            })
+    }
+
+    fn handle_locked_mode(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Enter => self.unlock(),
+            _ => {}
+        }
+    }
+
     fn check_control_quit(&mut self, key: KeyEvent) -> bool {
         if matches!(key.code, KeyCode::Char('q' | 'c'))
             && key.modifiers.contains(KeyModifiers::CONTROL)
@@ -485,10 +1584,10 @@ impl App {
     }
 
     fn handle_tab_key(&mut self, is_shift: bool) {
-        self.input_field = match (self.input_field, is_shift) {
-            (0, true) | (1, false) => 0,
-            (0, false) | (1, true) => 1,
-            _ => unreachable!(),
+        self.input_field = if is_shift {
+            (self.input_field + ENTRY_FIELDS - 1) % ENTRY_FIELDS
+        } else {
+            (self.input_field + 1) % ENTRY_FIELDS
         };
     }
 
@@ -529,13 +1628,31 @@ impl App {
 
     fn reset_entry_state(&mut self) {
         let fields = match self.input_mode {
-            InputMode::Adding => (&mut self.new_entry_name, &mut self.new_entry_secret),
-            _ => (&mut self.edit_entry_name, &mut self.edit_entry_secret),
+            InputMode::Adding => (
+                &mut self.new_entry_name,
+                &mut self.new_entry_secret,
+                &mut self.new_entry_issuer,
+                &mut self.new_entry_account,
+                &mut self.new_entry_tags,
+                &mut self.new_entry_template,
+            ),
+            _ => (
+                &mut self.edit_entry_name,
+                &mut self.edit_entry_secret,
+                &mut self.edit_entry_issuer,
+                &mut self.edit_entry_account,
+                &mut self.edit_entry_tags,
+                &mut self.edit_entry_template,
+            ),
         };
 
         self.input_mode = InputMode::Normal;
         fields.0.clear();
-        fields.1.clear();
+        fields.1.zeroize();
+        fields.2.clear();
+        fields.3.clear();
+        fields.4.clear();
+        fields.5.zeroize();
         self.input_field = 0;
     }
 
@@ -579,8 +1696,7 @@ impl App {
     }
 
     fn update_entry_field(&mut self, c: char) {
-        let field = self.get_current_field();
-        field.push(c);
+        push_wiped(self.get_current_field(), c);
     }
 
     fn remove_entry_char(&mut self) {
@@ -589,46 +1705,72 @@ impl App {
     }
 
     fn get_current_field(&mut self) -> &mut String {
-        match (self.input_mode.clone(), self.input_field == 0) {
-            (InputMode::Adding, true) => &mut self.new_entry_name,
-            (InputMode::Adding, false) => &mut self.new_entry_secret,
-            (_, true) => &mut self.edit_entry_name,
-            (_, false) => &mut self.edit_entry_secret,
+        match (self.input_mode.clone(), self.input_field) {
+            (InputMode::Adding, 0) => &mut self.new_entry_name,
+            (InputMode::Adding, 1) => &mut self.new_entry_secret,
+            (InputMode::Adding, 2) => &mut self.new_entry_issuer,
+            (InputMode::Adding, 3) => &mut self.new_entry_account,
+            (InputMode::Adding, 4) => &mut self.new_entry_tags,
+            (InputMode::Adding, _) => &mut self.new_entry_template,
+            (_, 0) => &mut self.edit_entry_name,
+            (_, 1) => &mut self.edit_entry_secret,
+            (_, 2) => &mut self.edit_entry_issuer,
+            (_, 3) => &mut self.edit_entry_account,
+            (_, 4) => &mut self.edit_entry_tags,
+            (_, _) => &mut self.edit_entry_template,
         }
     }
 
     fn edit_entry(&mut self) {
-        if self.entries.is_empty() {
-            return;
-        }
-
         if !self.validate_edit_entry() {
             return;
         }
 
-        self.update_entry();
-        self.try_save_entries();
+        let found = self
+            .editing_id
+            .take()
+            .and_then(|id| self.entries.iter().position(|e| e.id == id));
+        match found {
+            Some(index) => {
+                self.selected = index;
+                self.update_entry();
+            }
+            None => self.show_error(&AuthError::EntryChangedError.to_string()),
+        }
     }
 
     fn validate_edit_entry(&mut self) -> bool {
+        if let Err(e) = expand_otpauth(
+            &mut self.edit_entry_name,
+            &mut self.edit_entry_secret,
+            &mut self.edit_entry_issuer,
+            &mut self.edit_entry_account,
+        ) {
+            self.show_error(&e.to_string());
+            return false;
+        }
         if self.edit_entry_name.is_empty() || self.edit_entry_secret.is_empty() {
             self.show_error(&AuthError::EmptyEntryError.to_string());
             return false;
         }
+        if !is_valid_template(&self.edit_entry_template) {
+            self.show_error(&AuthError::TemplateError.to_string());
+            return false;
+        }
         true
     }
 
     fn update_entry(&mut self) {
-        self.entries[self.selected] = Entry {
-            name: self.edit_entry_name.clone(),
-            secret: self.edit_entry_secret.clone(),
-        };
-    }
-
-    fn try_save_entries(&mut self) {
-        if self.save_entries().is_err() {
-            self.show_error(&AuthError::SaveError.to_string());
-        }
+        let label = format!("edit \"{}\"", self.entries[self.selected].name);
+        let before = self.vault_state();
+        let entry = &mut self.entries[self.selected];
+        entry.name.clone_from(&self.edit_entry_name);
+        entry.issuer.clone_from(&self.edit_entry_issuer);
+        entry.account.clone_from(&self.edit_entry_account);
+        entry.tags = parse_tags(&self.edit_entry_tags);
+        entry.secret.clone_from(&self.edit_entry_secret);
+        entry.template.clone_from(&self.edit_entry_template);
+        self.commit_change(label, before);
     }
 
     fn start_editing(&mut self) {
@@ -638,23 +1780,57 @@ impl App {
 
         let entry = &self.entries[self.selected];
         self.edit_entry_name = entry.name.clone();
-        self.edit_entry_secret = entry.secret.clone();
+        self.edit_entry_secret.zeroize();
+        self.edit_entry_secret.push_str(&entry.secret);
+        self.edit_entry_issuer.clone_from(&entry.issuer);
+        self.edit_entry_account.clone_from(&entry.account);
+        self.edit_entry_tags = format_tags(&entry.tags);
+        self.edit_entry_template.zeroize();
+        self.edit_entry_template.push_str(&entry.template);
+        self.editing_id = Some(entry.id);
         self.input_mode = InputMode::Editing;
         self.input_field = 0;
     }
 
     fn next_entry(&mut self) {
-        let Some(len) = (!self.entries.is_empty()).then_some(self.entries.len()) else {
-            return;
-        };
-        self.selected = (self.selected + 1) % len;
+        self.move_selection(|position, len| (position + 1) % len);
     }
 
     fn previous_entry(&mut self) {
-        let Some(len) = (!self.entries.is_empty()).then_some(self.entries.len()) else {
+        self.move_selection(|position, len| position.checked_sub(1).unwrap_or(len - 1));
+    }
+
+    fn next_page(&mut self, page: usize) {
+        let page = page.max(1);
+        self.move_selection(|position, len| (position + page).min(len - 1));
+    }
+
+    fn previous_page(&mut self, page: usize) {
+        let page = page.max(1);
+        self.move_selection(|position, _| position.saturating_sub(page));
+    }
+
+    fn first_entry(&mut self) {
+        self.move_selection(|_, _| 0);
+    }
+
+    fn last_entry(&mut self) {
+        self.move_selection(|_, len| len - 1);
+    }
+
+    /// Moves the cursor within the visible entries, `step` mapping the current position and
+    /// the number of visible entries to the new position.
+    fn move_selection(&mut self, step: impl Fn(usize, usize) -> usize) {
+        let visible = self.visible_entries();
+        if visible.is_empty() {
             return;
-        };
-        self.selected = self.selected.checked_sub(1).unwrap_or(len - 1);
+        }
+
+        let position = visible
+            .iter()
+            .position(|m| m.index == self.selected)
+            .map_or(0, |position| step(position, visible.len()));
+        self.selected = visible[position].index;
     }
 
     fn handle_file_browser_mode(&mut self, key: KeyEvent) {
@@ -715,3 +1891,70 @@ impl App {
         }
     }
 }
+
+/// Replaces an `otpauth://` URI pasted as the secret with the secret it holds, filling the
+/// issuer, account and name from it where they were left empty.
+fn expand_otpauth(
+    name: &mut String,
+    secret: &mut Secret,
+    issuer: &mut String,
+    account: &mut String,
+) -> AuthResult<()> {
+    if !is_otpauth_uri(secret) {
+        return Ok(());
+    }
+
+    let parsed = parse_otpauth(secret)?;
+    *secret = parsed.secret;
+    if issuer.is_empty() {
+        *issuer = parsed.issuer;
+    }
+    if account.is_empty() {
+        *account = parsed.account;
+    }
+    if name.is_empty() {
+        name.clone_from(if issuer.is_empty() { account } else { issuer });
+    }
+    Ok(())
+}
+
+/// Notification for entries another instance changed, e.g. "Vault changed on disk: 1 added,
+/// 2 edited". Changes that add, edit or remove nothing, like a reorder, get the bare message.
+fn describe_external_change(previous: &[Entry], current: &[Entry]) -> String {
+    let (mut added, mut edited) = (0, 0);
+    for entry in current {
+        match previous.iter().find(|e| e.id == entry.id) {
+            Some(before) if !before.same_content(entry) => edited += 1,
+            Some(_) => {}
+            None => added += 1,
+        }
+    }
+    let removed = previous
+        .iter()
+        .filter(|e| !current.iter().any(|entry| entry.id == e.id))
+        .count();
+
+    let counts: Vec<String> = [(added, "added"), (edited, "edited"), (removed, "removed")]
+        .into_iter()
+        .filter(|&(count, _)| count > 0)
+        .map(|(count, change)| format!("{count} {change}"))
+        .collect();
+    if counts.is_empty() {
+        "Vault changed on disk".to_string()
+    } else {
+        format!("Vault changed on disk: {}", counts.join(", "))
+    }
+}
+
+/// An empty template copies the bare code, anything else has to place it somewhere.
+fn is_valid_template(template: &str) -> bool {
+    template.is_empty() || template.contains(CODE_PLACEHOLDER)
+}
+
+/// Copies decrypted bytes into a string that is wiped on drop, unlike `String::from_utf8`
+/// whose error path hands the plaintext back in an ordinary `Vec`.
+fn decode_utf8(data: &[u8]) -> AuthResult<Secret> {
+    std::str::from_utf8(data)
+        .map(|s| Zeroizing::new(s.to_owned()))
+        .map_err(|_| AuthError::Utf8Error)
+}
@@ -0,0 +1,169 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::utils::time::unix_millis;
+use crate::{AuthError, AuthResult};
+
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "entries-";
+const BACKUP_SUFFIX: &str = ".toml";
+const MIRROR_FILE: &str = "entries.toml";
+
+/// How often the mirror's modification time is re-read, in case another instance wrote it.
+const MIRROR_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct Snapshot {
+    pub path: PathBuf,
+    /// Milliseconds since the unix epoch at which the snapshot was taken
+    pub timestamp: u64,
+}
+
+pub struct Backups {
+    dir: PathBuf,
+    retention: usize,
+}
+
+impl Backups {
+    #[must_use]
+    pub fn new(auth_dir: &Path, retention: usize) -> Self {
+        Self {
+            dir: auth_dir.join(BACKUP_DIR),
+            retention,
+        }
+    }
+
+    /// Copies the current encrypted vault into the backup directory before it gets overwritten,
+    /// then drops generations beyond the configured retention.
+    ///
+    /// # Errors
+    pub fn snapshot(&self, entries_path: &Path) -> AuthResult<()> {
+        if self.retention == 0 || !entries_path.exists() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.dir).map_err(|_| AuthError::CreateDirError)?;
+        // Saves within the same millisecond get the next free timestamp instead of overwriting
+        // the previous snapshot.
+        let newest = self.list().first().map_or(0, |s| s.timestamp);
+        let timestamp = unix_millis().max(newest + 1);
+        let name = format!("{BACKUP_PREFIX}{timestamp}{BACKUP_SUFFIX}");
+        fs::copy(entries_path, self.dir.join(name)).map_err(|_| AuthError::BackupError)?;
+
+        self.prune()
+    }
+
+    /// Lists snapshots, newest first.
+    #[must_use]
+    pub fn list(&self) -> Vec<Snapshot> {
+        let Ok(read_dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        let mut snapshots = read_dir
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let path = entry.path();
+                let timestamp = Self::parse_timestamp(&path)?;
+                Some(Snapshot { path, timestamp })
+            })
+            .collect::<Vec<_>>();
+
+        snapshots.sort_by_key(|s| Reverse(s.timestamp));
+        snapshots
+    }
+
+    fn parse_timestamp(path: &Path) -> Option<u64> {
+        path.file_name()?
+            .to_str()?
+            .strip_prefix(BACKUP_PREFIX)?
+            .strip_suffix(BACKUP_SUFFIX)?
+            .parse()
+            .ok()
+    }
+
+    fn prune(&self) -> AuthResult<()> {
+        for snapshot in self.list().into_iter().skip(self.retention) {
+            fs::remove_file(snapshot.path).map_err(|_| AuthError::BackupError)?;
+        }
+        Ok(())
+    }
+}
+
+pub enum MirrorStatus {
+    Succeeded(SystemTime),
+    Failed(SystemTime),
+}
+
+/// Mirror directory and when its copy was last written, cached so that drawing the status does
+/// not touch the filesystem.
+pub struct Mirror {
+    dir: PathBuf,
+    reminder_days: u64,
+    last_written: Option<SystemTime>,
+    last_checked: Instant,
+}
+
+impl Mirror {
+    #[must_use]
+    pub fn new(dir: PathBuf, reminder_days: u64) -> Self {
+        let last_written = read_modified(&dir);
+        Self {
+            dir,
+            reminder_days,
+            last_written,
+            last_checked: Instant::now(),
+        }
+    }
+
+    /// Re-reads when the mirror was last written once the refresh interval has passed.
+    pub fn refresh(&mut self) {
+        if self.last_checked.elapsed() >= MIRROR_REFRESH_INTERVAL {
+            self.last_written = read_modified(&self.dir);
+            self.last_checked = Instant::now();
+        }
+    }
+
+    /// Writes the encrypted vault into the mirror directory through a temporary file, so an
+    /// interrupted copy never replaces the previous mirror with a truncated one.
+    ///
+    /// # Errors
+    pub fn write(&mut self, encrypted: &[u8]) -> AuthResult<()> {
+        if !self.dir.is_dir() {
+            return Err(AuthError::MirrorError);
+        }
+
+        let tmp_path = self.dir.join(format!("{MIRROR_FILE}.tmp"));
+        fs::write(&tmp_path, encrypted).map_err(|_| AuthError::MirrorError)?;
+        fs::rename(&tmp_path, self.dir.join(MIRROR_FILE)).map_err(|_| AuthError::MirrorError)?;
+        self.last_written = Some(SystemTime::now());
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn last_written(&self) -> Option<SystemTime> {
+        self.last_written
+    }
+
+    /// Whether the reminder threshold passed without a successful mirror.
+    #[must_use]
+    pub fn is_overdue(&self) -> bool {
+        self.reminder_days != 0
+            && self
+                .days_since_written()
+                .is_none_or(|days| days >= self.reminder_days)
+    }
+
+    #[must_use]
+    pub fn days_since_written(&self) -> Option<u64> {
+        self.last_written()
+            .map(|time| time.elapsed().unwrap_or(Duration::ZERO).as_secs() / 86_400)
+    }
+}
+
+fn read_modified(dir: &Path) -> Option<SystemTime> {
+    fs::metadata(dir.join(MIRROR_FILE))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
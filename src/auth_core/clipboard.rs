@@ -0,0 +1,221 @@
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::io::Read;
+use std::io::Write;
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use arboard::Clipboard;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Deserialize;
+use zeroize::Zeroizing;
+
+use crate::auth_core::entry::Secret;
+use crate::utils::config::ClipboardConfig;
+use crate::{AuthError, AuthResult};
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardBackend {
+    /// System clipboard, falling back to OSC 52 when it is unavailable (SSH, containers)
+    #[default]
+    Auto,
+    System,
+    /// Escape sequence asking the terminal emulator to set the clipboard of the machine it runs on
+    Osc52,
+}
+
+/// Clipboard that remembers the last code it placed, so it can wipe it again after the
+/// configured timeout without touching anything the user copied since.
+pub struct CodeClipboard {
+    clipboard: Option<Clipboard>,
+    osc52: bool,
+    copied: Option<(Secret, Instant)>,
+    clear_after: Option<Duration>,
+    keep_after_exit: bool,
+}
+
+impl CodeClipboard {
+    #[must_use]
+    pub fn new(config: &ClipboardConfig) -> Self {
+        let clipboard = match config.backend {
+            ClipboardBackend::Osc52 => None,
+            ClipboardBackend::Auto | ClipboardBackend::System => Clipboard::new().ok(),
+        };
+        let osc52 = config.backend == ClipboardBackend::Osc52
+            || (config.backend == ClipboardBackend::Auto && clipboard.is_none());
+
+        Self {
+            clipboard,
+            osc52,
+            copied: None,
+            clear_after: (config.clear_after_secs > 0)
+                .then(|| Duration::from_secs(config.clear_after_secs)),
+            keep_after_exit: config.keep_after_exit,
+        }
+    }
+
+    /// Copies through the system clipboard, or through OSC 52. The latter cannot be read back,
+    /// so such copies are neither auto-cleared nor handed off on exit.
+    ///
+    /// # Errors
+    pub fn copy(&mut self, text: &str) -> AuthResult<()> {
+        if self.osc52 {
+            self.copied = None;
+            return write_osc52(text);
+        }
+
+        let clipboard = self
+            .clipboard
+            .as_mut()
+            .ok_or(AuthError::ClipboardInitializeError)?;
+        set_sensitive_text(clipboard, text).map_err(|_| AuthError::ClipboardError)?;
+
+        self.copied = Some((Zeroizing::new(text.to_string()), Instant::now()));
+        Ok(())
+    }
+
+    /// Seconds left until the copied code is cleared, if auto-clear is pending.
+    #[must_use]
+    pub fn clear_countdown(&self) -> Option<u64> {
+        let (_, time) = self.copied.as_ref()?;
+        let remaining = self.clear_after?.checked_sub(time.elapsed())?;
+        Some(remaining.as_secs() + 1)
+    }
+
+    /// Clears the clipboard once the timeout has passed, but only if it still holds our code.
+    pub fn clear_if_expired(&mut self) {
+        let Some(clear_after) = self.clear_after else {
+            return;
+        };
+        if self
+            .copied
+            .as_ref()
+            .is_none_or(|(_, time)| time.elapsed() < clear_after)
+        {
+            return;
+        }
+
+        let Some((code, _)) = self.copied.take() else {
+            return;
+        };
+        if let Some(clipboard) = self.clipboard.as_mut()
+            && clipboard
+                .get_text()
+                .is_ok_and(|current| Zeroizing::new(current) == code)
+        {
+            clipboard.clear().ok();
+        }
+    }
+}
+
+impl CodeClipboard {
+    /// X11 and Wayland selections are served by the process that owns them, so a copied code
+    /// vanishes when the app exits. Hands a still-pending code to a detached copy of this binary
+    /// that keeps serving it until it is replaced or the auto-clear timeout fires.
+    ///
+    /// # Errors
+    #[cfg(all(unix, not(target_os = "macos")))]
+    pub fn hand_off(&mut self) -> AuthResult<()> {
+        use std::os::unix::process::CommandExt;
+
+        if !self.keep_after_exit {
+            return Ok(());
+        }
+        let Some((code, _)) = self.copied.as_ref() else {
+            return Ok(());
+        };
+        let still_ours = self.clipboard.as_mut().is_some_and(|clipboard| {
+            clipboard
+                .get_text()
+                .is_ok_and(|current| Zeroizing::new(current) == *code)
+        });
+        if !still_ours {
+            return Ok(());
+        }
+
+        // `0` serves the code until it is replaced, so an expired auto-clear must not fall back
+        // to it.
+        let serve_secs = match (self.clear_after, self.clear_countdown()) {
+            (None, _) => 0,
+            (Some(_), Some(secs)) => secs,
+            (Some(_), None) => return Ok(()),
+        };
+        let mut helper = Command::new(std::env::current_exe()?)
+            .arg("--serve-clipboard")
+            .arg(serve_secs.to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()?;
+
+        // The code goes through stdin so it never shows up in the process list.
+        if let Some(mut stdin) = helper.stdin.take() {
+            stdin.write_all(code.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Clipboards on other platforms keep their contents after the owner exits.
+    ///
+    /// # Errors
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    pub fn hand_off(&mut self) -> AuthResult<()> {
+        Ok(())
+    }
+}
+
+/// Entry point of the helper spawned by [`CodeClipboard::hand_off`]: reads the code from stdin
+/// and serves it for `secs` seconds, or until it is replaced when `secs` is `0`.
+///
+/// # Errors
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn serve(secs: u64) -> AuthResult<()> {
+    use arboard::SetExtLinux;
+
+    let mut code = Zeroizing::new(String::new());
+    std::io::stdin().read_to_string(&mut code)?;
+
+    let mut clipboard = Clipboard::new().map_err(|_| AuthError::ClipboardInitializeError)?;
+    let set = clipboard.set().exclude_from_history();
+    let set = if secs == 0 {
+        set.wait()
+    } else {
+        set.wait_until(Instant::now() + Duration::from_secs(secs))
+    };
+    set.text(code.as_str())
+        .map_err(|_| AuthError::ClipboardError)
+}
+
+/// # Errors
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn serve(_secs: u64) -> AuthResult<()> {
+    Ok(())
+}
+
+fn write_osc52(text: &str) -> AuthResult<()> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Marks the content as sensitive so clipboard managers keep it out of their history.
+fn set_sensitive_text(clipboard: &mut Clipboard, text: &str) -> Result<(), arboard::Error> {
+    let set = clipboard.set();
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let set = arboard::SetExtLinux::exclude_from_history(set);
+
+    #[cfg(target_os = "macos")]
+    let set = arboard::SetExtApple::exclude_from_history(set);
+
+    #[cfg(windows)]
+    let set = arboard::SetExtWindows::exclude_from_cloud(
+        arboard::SetExtWindows::exclude_from_history(set),
+    );
+
+    set.text(text)
+}
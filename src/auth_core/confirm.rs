@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+/// Destructive action waiting for the user to confirm it. Single-entry actions carry the entry's
+/// id and name, so a reload while the popup is open cannot redirect them to another entry.
+#[derive(Clone, PartialEq)]
+pub enum ConfirmAction {
+    DeleteEntry { id: u64, name: String },
+    DeleteAll,
+    OverwriteExport(PathBuf),
+    PurgeTrashEntry { id: u64, name: String },
+    EmptyTrash,
+}
+
+/// Confirmation popup shown on top of whatever mode the app is in. Actions with a phrase only
+/// go ahead once it has been typed exactly, the rest take a plain `y`.
+pub struct Confirmation {
+    pub action: ConfirmAction,
+    pub message: String,
+    pub phrase: Option<String>,
+    pub input: String,
+}
+
+impl Confirmation {
+    #[must_use]
+    pub fn new(action: ConfirmAction, message: String) -> Self {
+        Self {
+            action,
+            message,
+            phrase: None,
+            input: String::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_phrase(mut self, phrase: &str) -> Self {
+        self.phrase = Some(phrase.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn is_satisfied(&self) -> bool {
+        self.phrase
+            .as_ref()
+            .is_none_or(|phrase| &self.input == phrase)
+    }
+}
@@ -6,9 +6,14 @@ use std::str::FromStr;
 use age::Encryptor;
 use age::secrecy::ExposeSecret;
 use age::x25519::Identity;
+use zeroize::Zeroizing;
 
+use crate::utils::hardening::LockedBuffer;
 use crate::{AuthError, AuthResult};
 
+/// Name of the unencrypted age identity in the auth directory.
+pub const KEY_FILE: &str = "key";
+
 pub struct Crypto {
     identity: Identity,
 }
@@ -16,9 +21,9 @@ pub struct Crypto {
 impl Crypto {
     /// # Errors
     pub fn new(auth_dir: &Path) -> AuthResult<Self> {
-        let key_path = auth_dir.join("key");
+        let key_path = auth_dir.join(KEY_FILE);
         let identity = if key_path.exists() {
-            let key_str = fs::read_to_string(&key_path)?;
+            let key_str = Zeroizing::new(fs::read_to_string(&key_path)?);
             Identity::from_str(&key_str).map_err(|e| AuthError::InvalidKey(e.to_string()))?
         } else {
             let identity = Identity::generate();
@@ -44,10 +49,13 @@ impl Crypto {
         Ok(encrypted)
     }
 
+    /// Decrypts into a [`LockedBuffer`]; the ciphertext is always longer than the plaintext, so
+    /// its length is enough capacity.
+    ///
     /// # Errors
-    pub fn decrypt(&self, data: &[u8]) -> AuthResult<Vec<u8>> {
+    pub fn decrypt(&self, data: &[u8]) -> AuthResult<LockedBuffer> {
         let decryptor = age::Decryptor::new(data)?;
-        let mut decrypted = vec![];
+        let mut decrypted = LockedBuffer::with_capacity(data.len());
         let mut reader =
             decryptor.decrypt(std::iter::once(&self.identity as &dyn age::Identity))?;
         std::io::copy(&mut reader, &mut decrypted)?;
@@ -1,19 +1,114 @@
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
 
-use crate::auth_core::totp::generate_totp;
+use crate::auth_core::migration::VAULT_VERSION;
+use crate::auth_core::sort::SortMode;
+use crate::auth_core::totp::{PERIOD, generate_totp, generate_totp_at};
+use crate::utils::time::unix_millis;
 
-#[derive(Serialize, Deserialize)]
+/// String holding secret material, wiped from memory when dropped.
+pub type Secret = Zeroizing<String>;
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Entries {
+    /// Layout version, see `auth_core::migration`
+    #[serde(default)]
+    pub version: u32,
     pub entries: Vec<Entry>,
+    /// Deleted entries, kept until they are restored, purged or expire
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trash: Vec<TrashedEntry>,
+    #[serde(default, skip_serializing_if = "is_manual")]
+    pub sort: SortMode,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+impl Default for Entries {
+    fn default() -> Self {
+        Self {
+            version: VAULT_VERSION,
+            entries: Vec::new(),
+            trash: Vec::new(),
+            sort: SortMode::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct TrashedEntry {
+    pub entry: Entry,
+    /// Seconds since the Unix epoch
+    pub deleted_at: u64,
+}
+
+/// Placeholder in a copy template replaced by the current code.
+pub const CODE_PLACEHOLDER: &str = "{code}";
+
+/// Placeholder in a copy template replaced by a newline, for forms that submit on Enter.
+pub const ENTER_PLACEHOLDER: &str = "{enter}";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
 pub struct Entry {
+    /// Stays the same across edits, moves and instances, so merges and undo can tell an edited
+    /// entry from a replaced one. `0` until one is assigned.
+    #[serde(default)]
+    pub id: u64,
     pub name: String,
-    pub secret: String,
+    /// Service the code is for, e.g. `GitHub`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub issuer: String,
+    /// User name or email on that service
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub account: String,
+    /// Groups such as `work`, shown as tabs above the list
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub secret: Secret,
+    /// What gets copied instead of the bare code, e.g. `1234{code}` for a PIN prefix
+    #[serde(default, skip_serializing_if = "is_empty")]
+    pub template: Secret,
+    /// Seconds since the Unix epoch of the last copy, `0` if never copied
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub last_used: u64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub use_count: u64,
 }
 
 impl Entry {
+    /// Shown in the first column of the list: the issuer, or the name for entries without one.
+    #[must_use]
+    pub fn title(&self) -> &str {
+        if self.issuer.is_empty() {
+            &self.name
+        } else {
+            &self.issuer
+        }
+    }
+
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Seconds each code stays valid.
+    #[must_use]
+    pub fn period(&self) -> u64 {
+        PERIOD
+    }
+
+    /// Applies the copy template to `code`, or returns `code` as is without one.
+    #[must_use]
+    pub fn format_code(&self, code: &str) -> Secret {
+        if self.template.is_empty() {
+            return Zeroizing::new(code.to_string());
+        }
+
+        Zeroizing::new(
+            self.template
+                .replace(CODE_PLACEHOLDER, code)
+                .replace(ENTER_PLACEHOLDER, "\n"),
+        )
+    }
+
     #[must_use]
     pub fn generate_totp_with_time(&self) -> (String, u64) {
         match generate_totp(&self.secret) {
@@ -21,4 +116,34 @@ impl Entry {
             Err(_) => ("Invalid".to_string(), 0),
         }
     }
+
+    #[must_use]
+    pub fn generate_totp_at(&self, time: u64) -> (String, u64) {
+        match generate_totp_at(&self.secret, time) {
+            Ok(result) => result,
+            Err(_) => ("Invalid".to_string(), 0),
+        }
+    }
+}
+
+/// Random id for a new entry. TOML integers are signed, so the top bit stays clear.
+#[must_use]
+pub fn new_entry_id() -> u64 {
+    let random = getrandom::u64().unwrap_or_else(|_| unix_millis());
+    (random & (u64::MAX >> 1)).max(1)
+}
+
+fn is_empty(secret: &Secret) -> bool {
+    secret.is_empty()
+}
+
+// `skip_serializing_if` hands fields over by reference.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_manual(sort: &SortMode) -> bool {
+    *sort == SortMode::Manual
 }
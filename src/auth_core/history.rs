@@ -0,0 +1,62 @@
+/// Mutations kept for undo, oldest dropped first.
+pub const HISTORY_LIMIT: usize = 50;
+
+/// A saved mutation, as the vault looked right before and right after it.
+#[derive(Clone)]
+pub struct Change<T> {
+    pub label: String,
+    pub before: T,
+    pub after: T,
+}
+
+/// Session-only undo and redo stacks of saved mutations. Undoing replays `after` to `before` on
+/// top of the current vault rather than restoring `before` wholesale, so changes made since,
+/// e.g. by another instance, are kept.
+pub struct History<T> {
+    undo: Vec<Change<T>>,
+    redo: Vec<Change<T>>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone> History<T> {
+    /// Remembers the mutation described by `label`. Any redo history is dropped, as it no longer
+    /// follows from the current state.
+    pub fn record(&mut self, label: String, before: T, after: T) {
+        self.undo.push(Change {
+            label,
+            before,
+            after,
+        });
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Returns the latest mutation to revert, keeping it for redo.
+    pub fn undo(&mut self) -> Option<Change<T>> {
+        let change = self.undo.pop()?;
+        self.redo.push(change.clone());
+        Some(change)
+    }
+
+    /// Returns the latest undone mutation to apply again, keeping it for undo.
+    pub fn redo(&mut self) -> Option<Change<T>> {
+        let change = self.redo.pop()?;
+        self.undo.push(change.clone());
+        Some(change)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
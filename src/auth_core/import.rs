@@ -0,0 +1,248 @@
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::auth_core::entry::{Entry, Secret};
+use crate::auth_core::otpauth::split_label;
+use crate::auth_core::totp::is_supported;
+use crate::{AuthError, AuthResult};
+
+/// Entries read from another authenticator's export, and how many of its entries use settings
+/// auth cannot generate codes for.
+pub struct Imported {
+    pub entries: Vec<Entry>,
+    pub skipped: usize,
+}
+
+#[derive(Deserialize)]
+struct AegisExport {
+    db: AegisDb,
+}
+
+#[derive(Deserialize)]
+struct AegisDb {
+    entries: Vec<AegisEntry>,
+    #[serde(default)]
+    groups: Vec<AegisGroup>,
+}
+
+#[derive(Deserialize)]
+struct AegisGroup {
+    uuid: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct AegisEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    issuer: String,
+    /// Group name in exports before Aegis 3.0
+    #[serde(default)]
+    group: Option<String>,
+    /// Group uuids in later exports
+    #[serde(default)]
+    groups: Vec<String>,
+    info: AegisInfo,
+}
+
+#[derive(Deserialize)]
+struct AegisInfo {
+    secret: Secret,
+    #[serde(default)]
+    algo: Option<String>,
+    #[serde(default)]
+    digits: Option<usize>,
+    #[serde(default)]
+    period: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct AndOtpEntry {
+    secret: Secret,
+    #[serde(default)]
+    issuer: String,
+    #[serde(default)]
+    label: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    algorithm: Option<String>,
+    #[serde(default)]
+    digits: Option<usize>,
+    #[serde(default)]
+    period: Option<u64>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Layout of an export, told apart by walking it without keeping any of its contents, so the
+/// secrets are only ever read into the typed structs above.
+enum ExportShape {
+    Aegis,
+    EncryptedAegis,
+    AndOtp,
+    Unknown,
+}
+
+impl<'de> Deserialize<'de> for ExportShape {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ShapeVisitor { in_db: false })
+    }
+}
+
+/// Tells an andOTP array from an Aegis object, and within the latter a plain `db` object from
+/// an encrypted `db` string.
+struct ShapeVisitor {
+    in_db: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for ShapeVisitor {
+    type Value = ExportShape;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<ExportShape, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ShapeVisitor {
+    type Value = ExportShape;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an Aegis or andOTP export")
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> Result<ExportShape, E> {
+        Ok(if self.in_db {
+            ExportShape::EncryptedAegis
+        } else {
+            ExportShape::Unknown
+        })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ExportShape, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(if self.in_db {
+            ExportShape::Unknown
+        } else {
+            ExportShape::AndOtp
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ExportShape, A::Error> {
+        if self.in_db {
+            while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+            return Ok(ExportShape::Aegis);
+        }
+
+        let mut shape = ExportShape::Unknown;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "db" {
+                shape = map.next_value_seed(ShapeVisitor { in_db: true })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(shape)
+    }
+}
+
+/// Reads an unencrypted Aegis or andOTP JSON export, keeping their groups and tags as tags.
+///
+/// # Errors
+pub fn parse_json_export(contents: &str) -> AuthResult<Imported> {
+    serde_json::from_str::<IgnoredAny>(contents).map_err(|_| AuthError::ParseError)?;
+    match serde_json::from_str(contents) {
+        Ok(ExportShape::Aegis) => parse_aegis(contents),
+        Ok(ExportShape::EncryptedAegis) => Err(AuthError::EncryptedImportError),
+        Ok(ExportShape::AndOtp) => parse_andotp(contents),
+        Ok(ExportShape::Unknown) | Err(_) => Err(AuthError::ImportFormatError),
+    }
+}
+
+fn parse_aegis(contents: &str) -> AuthResult<Imported> {
+    let AegisExport { db } = serde_json::from_str(contents).map_err(|_| AuthError::ParseError)?;
+    let group_name = |uuid: &String| {
+        db.groups
+            .iter()
+            .find(|group| &group.uuid == uuid)
+            .map(|group| group.name.clone())
+    };
+
+    let mut imported = Imported {
+        entries: Vec::new(),
+        skipped: 0,
+    };
+    for entry in &db.entries {
+        let info = &entry.info;
+        if !entry.kind.eq_ignore_ascii_case("totp")
+            || !is_supported(info.algo.as_deref(), info.digits, info.period)
+        {
+            imported.skipped += 1;
+            continue;
+        }
+
+        let tags = entry
+            .group
+            .iter()
+            .cloned()
+            .chain(entry.groups.iter().filter_map(group_name))
+            .collect();
+        imported.entries.push(create_entry(
+            entry.issuer.clone(),
+            entry.name.clone(),
+            info.secret.clone(),
+            tags,
+        ));
+    }
+    Ok(imported)
+}
+
+fn parse_andotp(contents: &str) -> AuthResult<Imported> {
+    let entries: Vec<AndOtpEntry> =
+        serde_json::from_str(contents).map_err(|_| AuthError::ParseError)?;
+
+    let mut imported = Imported {
+        entries: Vec::new(),
+        skipped: 0,
+    };
+    for entry in entries {
+        if !entry.kind.eq_ignore_ascii_case("totp")
+            || !is_supported(entry.algorithm.as_deref(), entry.digits, entry.period)
+        {
+            imported.skipped += 1;
+            continue;
+        }
+
+        let (label_issuer, account) = split_label(&entry.label);
+        let issuer = if entry.issuer.is_empty() {
+            label_issuer
+        } else {
+            entry.issuer
+        };
+        imported
+            .entries
+            .push(create_entry(issuer, account, entry.secret, entry.tags));
+    }
+    Ok(imported)
+}
+
+fn create_entry(issuer: String, account: String, secret: Secret, tags: Vec<String>) -> Entry {
+    let name = if issuer.is_empty() {
+        account.clone()
+    } else {
+        issuer.clone()
+    };
+    Entry {
+        name,
+        issuer,
+        account,
+        tags,
+        secret,
+        ..Entry::default()
+    }
+}
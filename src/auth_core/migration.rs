@@ -0,0 +1,85 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use serde::Deserialize;
+
+use crate::auth_core::entry::{Entries, Entry};
+use crate::auth_core::otpauth::split_label;
+use crate::{AuthError, AuthResult};
+
+/// Vault layout written by this build. Vaults without a version predate versioning and count as
+/// version 1.
+pub const VAULT_VERSION: u32 = 4;
+
+/// Step `i` upgrades a vault from version `i + 1` to `i + 2`. A layout change bumps
+/// `VAULT_VERSION` and appends its step here. So far every version only added fields with
+/// defaults, so older vaults read straight into `Entries` and the steps fill in the new fields;
+/// renaming or restructuring a field would need a typed struct for the old layout.
+const MIGRATIONS: [fn(&mut Entries); VAULT_VERSION as usize - 1] =
+    [split_legacy_names, add_tags, assign_ids];
+
+/// Only the version, read before the rest so that a newer layout is reported as such instead
+/// of failing to parse.
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: Option<i64>,
+}
+
+/// Parses a vault or export of any version up to `VAULT_VERSION`, upgrading older layouts.
+/// Secrets go straight into `Entries`, without an untyped copy of the document in between.
+///
+/// # Errors
+pub fn parse_vault(contents: &str) -> AuthResult<Entries> {
+    let probe: VersionProbe = toml::from_str(contents).map_err(|_| AuthError::ParseError)?;
+    let version = vault_version(probe.version)?;
+    if version > VAULT_VERSION {
+        return Err(AuthError::UnsupportedVersion(version));
+    }
+
+    let mut vault: Entries = toml::from_str(contents).map_err(|_| AuthError::ParseError)?;
+    for migrate in &MIGRATIONS[version as usize - 1..] {
+        migrate(&mut vault);
+    }
+    vault.version = VAULT_VERSION;
+    Ok(vault)
+}
+
+fn vault_version(version: Option<i64>) -> AuthResult<u32> {
+    let Some(version) = version else {
+        return Ok(1);
+    };
+
+    u32::try_from(version)
+        .ok()
+        .filter(|&version| version >= 1)
+        .ok_or(AuthError::ParseError)
+}
+
+/// Version 2 added issuer and account fields, which older entries kept in names of the form
+/// `Issuer:account`. The name itself is left as it was.
+fn split_legacy_names(vault: &mut Entries) {
+    let trashed = vault.trash.iter_mut().map(|trashed| &mut trashed.entry);
+    for entry in vault.entries.iter_mut().chain(trashed) {
+        split_legacy_name(entry);
+    }
+}
+
+fn split_legacy_name(entry: &mut Entry) {
+    if entry.issuer.is_empty() && entry.account.is_empty() && entry.name.contains(':') {
+        (entry.issuer, entry.account) = split_label(&entry.name);
+    }
+}
+
+/// Version 3 added tags. Older vaults have none, so there is nothing to convert; the bump only
+/// keeps older builds from dropping tags when they save.
+fn add_tags(_vault: &mut Entries) {}
+
+/// Version 4 identifies entries by id. The ids are derived from the entries rather than drawn at
+/// random, so that instances upgrading the same vault at once agree on them.
+fn assign_ids(vault: &mut Entries) {
+    let trashed = vault.trash.iter_mut().map(|trashed| &mut trashed.entry);
+    for (position, entry) in vault.entries.iter_mut().chain(trashed).enumerate() {
+        let mut hasher = DefaultHasher::new();
+        (position, &entry.name, entry.secret.as_str()).hash(&mut hasher);
+        entry.id = (hasher.finish() & (u64::MAX >> 1)).max(1);
+    }
+}
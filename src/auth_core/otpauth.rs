@@ -0,0 +1,98 @@
+use zeroize::Zeroizing;
+
+use crate::auth_core::entry::Secret;
+use crate::auth_core::totp::is_supported;
+use crate::{AuthError, AuthResult};
+
+const TOTP_PREFIX: &str = "otpauth://totp/";
+
+/// Fields of an `otpauth://totp/` key URI, as encoded in provisioning QR codes.
+pub struct OtpAuth {
+    pub secret: Secret,
+    pub issuer: String,
+    pub account: String,
+}
+
+#[must_use]
+pub fn is_otpauth_uri(text: &str) -> bool {
+    text.get(..TOTP_PREFIX.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(TOTP_PREFIX))
+}
+
+/// Parses an `otpauth://totp/Issuer:account?secret=...&issuer=...` URI. The `issuer` parameter
+/// takes precedence over the label prefix, as the key URI format recommends. URIs asking for an
+/// algorithm, digit count or period auth cannot generate are rejected.
+///
+/// # Errors
+pub fn parse_otpauth(uri: &str) -> AuthResult<OtpAuth> {
+    if !is_otpauth_uri(uri) {
+        return Err(AuthError::InvalidKey("Unsupported otpauth URI".to_string()));
+    }
+
+    let rest = &uri[TOTP_PREFIX.len()..];
+    let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (label_issuer, account) = split_label(&percent_decode(label)?);
+
+    let mut secret = None;
+    let mut issuer = None;
+    let mut algorithm = None;
+    let mut digits = None;
+    let mut period = None;
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "secret" => secret = Some(percent_decode(value)?),
+            "issuer" => issuer = Some(percent_decode(value)?.to_string()),
+            "algorithm" => algorithm = Some(value),
+            "digits" => digits = Some(parse_number(value)?),
+            "period" => period = Some(parse_number(value)?),
+            _ => {}
+        }
+    }
+    if !is_supported(algorithm, digits, period) {
+        return Err(AuthError::UnsupportedTotpError);
+    }
+
+    Ok(OtpAuth {
+        secret: secret
+            .ok_or_else(|| AuthError::InvalidKey("otpauth URI has no secret".to_string()))?,
+        issuer: issuer.unwrap_or(label_issuer),
+        account,
+    })
+}
+
+/// Splits an `Issuer:account` label, returning an empty issuer when there is no prefix.
+#[must_use]
+pub fn split_label(label: &str) -> (String, String) {
+    match label.split_once(':') {
+        Some((issuer, account)) => (issuer.trim().to_string(), account.trim().to_string()),
+        None => (String::new(), label.trim().to_string()),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> AuthResult<T> {
+    value.parse().map_err(|_| AuthError::UnsupportedTotpError)
+}
+
+/// Decodes `%XX` escapes. The buffer never outgrows its initial capacity and is zeroized, as it
+/// holds the secret.
+fn percent_decode(text: &str) -> AuthResult<Secret> {
+    let bytes = text.as_bytes();
+    let mut decoded = Zeroizing::new(Vec::with_capacity(bytes.len()));
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(byte) = hex {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    let decoded = std::str::from_utf8(&decoded)
+        .map_err(|_| AuthError::InvalidKey("otpauth URI is not valid UTF-8".to_string()))?;
+    Ok(Zeroizing::new(decoded.to_string()))
+}
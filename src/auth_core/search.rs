@@ -0,0 +1,88 @@
+use crate::auth_core::entry::Entry;
+
+/// Entry that matched a search query, with the character positions in its title to highlight.
+/// Matches on the account or name alone have nothing to highlight.
+pub struct SearchMatch {
+    pub index: usize,
+    pub positions: Vec<usize>,
+    score: usize,
+}
+
+/// Ranks matches outside the title below the title matches.
+const UNHIGHLIGHTED_PENALTY: usize = 1000;
+
+/// Marks a word of the query as a tag filter, e.g. `#work`.
+const TAG_PREFIX: char = '#';
+
+/// Filters the entries at `order` down to those whose title, account or name contains the query
+/// as a case-insensitive subsequence, best match first. Words starting with `#` instead keep
+/// entries with a tag starting with the rest of the word. An empty query keeps every entry in
+/// `order`.
+#[must_use]
+pub fn search(entries: &[Entry], order: &[usize], query: &str) -> Vec<SearchMatch> {
+    let (tags, words): (Vec<&str>, Vec<&str>) = query
+        .split_whitespace()
+        .partition(|word| word.starts_with(TAG_PREFIX));
+    let tags: Vec<String> = tags
+        .iter()
+        .filter_map(|word| word.strip_prefix(TAG_PREFIX))
+        .map(str::to_lowercase)
+        .collect();
+    let query = if tags.is_empty() {
+        query.to_string()
+    } else {
+        words.join(" ")
+    };
+
+    let mut matches: Vec<SearchMatch> = order
+        .iter()
+        .filter_map(|&index| {
+            let entry = &entries[index];
+            if !tags.iter().all(|prefix| has_tag_prefix(entry, prefix)) {
+                return None;
+            }
+            let (positions, score) = fuzzy_match(&query, entry.title()).or_else(|| {
+                [&entry.account, &entry.name]
+                    .into_iter()
+                    .find_map(|text| fuzzy_match(&query, text))
+                    .map(|(_, score)| (Vec::new(), score + UNHIGHLIGHTED_PENALTY))
+            })?;
+            Some(SearchMatch {
+                index,
+                positions,
+                score,
+            })
+        })
+        .collect();
+
+    matches.sort_by_key(|m| m.score);
+    matches
+}
+
+fn has_tag_prefix(entry: &Entry, prefix: &str) -> bool {
+    entry
+        .tags
+        .iter()
+        .any(|tag| tag.to_lowercase().starts_with(prefix))
+}
+
+/// Matches `query` against `text` greedily, returning the matched character positions and a
+/// score where lower is better: gaps between matched characters and a late start both count.
+#[must_use]
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(Vec<usize>, usize)> {
+    let mut positions = Vec::new();
+    let mut chars = text.chars().enumerate();
+
+    for q in query.chars().flat_map(char::to_lowercase) {
+        let (position, _) = chars
+            .by_ref()
+            .find(|(_, c)| c.to_lowercase().eq(std::iter::once(q)))?;
+        positions.push(position);
+    }
+
+    let score = positions.first().map_or(0, |&first| {
+        let gaps: usize = positions.windows(2).map(|w| w[1] - w[0] - 1).sum();
+        gaps * 2 + first
+    });
+    Some((positions, score))
+}
@@ -0,0 +1,63 @@
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+
+use crate::auth_core::entry::Entry;
+
+/// Order of the main list. Manual is the order entries are stored in, the others only change
+/// how they are displayed.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    #[default]
+    Manual,
+    Name,
+    Issuer,
+    Recent,
+    Frequent,
+}
+
+impl SortMode {
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Manual => Self::Name,
+            Self::Name => Self::Issuer,
+            Self::Issuer => Self::Recent,
+            Self::Recent => Self::Frequent,
+            Self::Frequent => Self::Manual,
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Manual => "manual order",
+            Self::Name => "name",
+            Self::Issuer => "issuer",
+            Self::Recent => "most recently used",
+            Self::Frequent => "most frequently used",
+        }
+    }
+}
+
+/// Indices of `entries` in display order for `mode`, ties kept in manual order.
+#[must_use]
+pub fn sorted_indices(entries: &[Entry], mode: SortMode) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..entries.len()).collect();
+    match mode {
+        SortMode::Manual => {}
+        SortMode::Name => indices.sort_by_key(|&i| entries[i].title().to_lowercase()),
+        SortMode::Issuer => indices.sort_by_key(|&i| {
+            let entry = &entries[i];
+            (
+                entry.issuer.is_empty(),
+                entry.issuer.to_lowercase(),
+                entry.account.to_lowercase(),
+            )
+        }),
+        SortMode::Recent => indices.sort_by_key(|&i| Reverse(entries[i].last_used)),
+        SortMode::Frequent => indices.sort_by_key(|&i| Reverse(entries[i].use_count)),
+    }
+    indices
+}
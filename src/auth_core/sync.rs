@@ -0,0 +1,201 @@
+use std::fs::{self, File, OpenOptions};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::auth_core::entry::{Entry, TrashedEntry};
+use crate::{AuthError, AuthResult};
+
+const LOCK_FILE: &str = ".lock";
+
+/// How often the running app checks whether the vault was changed on disk.
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Advisory lock on the auth directory, held while the vault is read or written so that
+/// several running instances never interleave their writes. Released on drop.
+pub struct VaultLock {
+    _file: File,
+}
+
+impl VaultLock {
+    /// Blocks until no other instance holds the lock.
+    ///
+    /// # Errors
+    pub fn acquire(auth_dir: &Path) -> AuthResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(auth_dir.join(LOCK_FILE))
+            .map_err(|_| AuthError::LockError)?;
+        file.lock().map_err(|_| AuthError::LockError)?;
+        Ok(Self { _file: file })
+    }
+}
+
+/// Fingerprint of the encrypted vault as last seen by this instance. Age output is randomized,
+/// so any rewrite by another process changes it even when the entries are identical.
+#[must_use]
+pub fn fingerprint(encrypted: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    encrypted.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Modification time and length of a file, compared before hashing it so that an untouched
+/// vault is not read on every check.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+/// Stamp of the file at `path`, `None` when it does not exist.
+#[must_use]
+pub fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+/// Fingerprint of the vault currently on disk, `None` when it does not exist.
+#[must_use]
+pub fn fingerprint_file(path: &Path) -> Option<u64> {
+    fs::read(path).ok().map(|encrypted| fingerprint(&encrypted))
+}
+
+/// Vault items that are merged by identity rather than equality, so that an edit, a reorder or
+/// a usage count bump on one side is not mistaken for a removal plus an addition.
+pub trait Mergeable: Clone {
+    fn id(&self) -> u64;
+
+    /// Whether both versions show the same thing, ignoring usage statistics.
+    fn same_content(&self, other: &Self) -> bool;
+
+    /// Adds the usage `other` recorded since `base` to this version.
+    fn merge_usage(&mut self, base: &Self, other: &Self);
+}
+
+impl Mergeable for Entry {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn same_content(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && self.issuer == other.issuer
+            && self.account == other.account
+            && self.tags == other.tags
+            && self.secret == other.secret
+            && self.template == other.template
+    }
+
+    fn merge_usage(&mut self, base: &Self, other: &Self) {
+        self.last_used = self.last_used.max(other.last_used);
+        self.use_count += other.use_count.saturating_sub(base.use_count);
+    }
+}
+
+impl Mergeable for TrashedEntry {
+    fn id(&self) -> u64 {
+        self.entry.id
+    }
+
+    fn same_content(&self, other: &Self) -> bool {
+        self.deleted_at == other.deleted_at && self.entry.same_content(&other.entry)
+    }
+
+    fn merge_usage(&mut self, base: &Self, other: &Self) {
+        self.entry.merge_usage(&base.entry, &other.entry);
+    }
+}
+
+/// Three-way merge of the items this instance loaded (`base`), its in-memory state (`local`)
+/// and what another instance wrote (`disk`), matched by id. Additions, removals and edits on
+/// either side survive; when both sides edited the same item the local edit wins, and an edit
+/// keeps an item the other side removed. Usage counts from both sides add up.
+///
+/// The order is taken from `local` when it reordered the items it shares with `base`, and from
+/// `disk` otherwise. Items only the other side has are placed next to their
+/// neighbours there.
+#[must_use]
+pub fn merge<T: Mergeable>(base: &[T], local: &[T], disk: &[T]) -> Vec<T> {
+    let (primary, secondary) = if is_reordered(base, local) {
+        (local, disk)
+    } else {
+        (disk, local)
+    };
+
+    let resolve_id = |id| resolve(find(base, id), find(local, id), find(disk, id));
+    let mut merged: Vec<T> = primary
+        .iter()
+        .filter_map(|item| resolve_id(item.id()))
+        .collect();
+
+    for (i, item) in secondary.iter().enumerate() {
+        if find(&merged, item.id()).is_some() {
+            continue;
+        }
+        let Some(item) = resolve_id(item.id()) else {
+            continue;
+        };
+        let position_of = |other: &T| merged.iter().position(|m| m.id() == other.id());
+        let position = secondary[..i]
+            .iter()
+            .rev()
+            .find_map(position_of)
+            .map(|position| position + 1)
+            .or_else(|| secondary[i + 1..].iter().find_map(position_of))
+            .unwrap_or(merged.len());
+        merged.insert(position, item);
+    }
+    merged
+}
+
+/// Whether both lists hold the same items with the same contents in the same order.
+#[must_use]
+pub fn is_unchanged<T: Mergeable>(before: &[T], after: &[T]) -> bool {
+    before.len() == after.len()
+        && before
+            .iter()
+            .zip(after)
+            .all(|(before, after)| before.same_content(after))
+}
+
+fn find<T: Mergeable>(items: &[T], id: u64) -> Option<&T> {
+    items.iter().find(|item| item.id() == id)
+}
+
+fn resolve<T: Mergeable>(base: Option<&T>, local: Option<&T>, disk: Option<&T>) -> Option<T> {
+    match (base, local, disk) {
+        (Some(base), Some(local), Some(disk)) => {
+            let (mut merged, other) = if local.same_content(base) {
+                (disk.clone(), local)
+            } else {
+                (local.clone(), disk)
+            };
+            merged.merge_usage(base, other);
+            Some(merged)
+        }
+        (Some(base), Some(kept), None) | (Some(base), None, Some(kept)) => {
+            (!kept.same_content(base)).then(|| kept.clone())
+        }
+        (None, Some(added), _) | (None, None, Some(added)) => Some(added.clone()),
+        (_, None, None) => None,
+    }
+}
+
+/// Whether `local` lists the items it shares with `base` in a different order.
+fn is_reordered<T: Mergeable>(base: &[T], local: &[T]) -> bool {
+    let shared = |items: &[T], other: &[T]| -> Vec<u64> {
+        items
+            .iter()
+            .map(Mergeable::id)
+            .filter(|&id| find(other, id).is_some())
+            .collect()
+    };
+    shared(base, local) != shared(local, base)
+}
@@ -0,0 +1,34 @@
+use crate::auth_core::entry::Entry;
+
+/// Separates tags in the add and edit popups.
+const TAG_SEPARATOR: char = ',';
+
+/// Splits comma separated input into tags, dropping blanks and case-insensitive repeats.
+#[must_use]
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(TAG_SEPARATOR).map(str::trim) {
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+#[must_use]
+pub fn format_tags(tags: &[String]) -> String {
+    tags.join(&format!("{TAG_SEPARATOR} "))
+}
+
+/// Every tag used by `entries`, sorted case-insensitively, as shown in the tab bar.
+#[must_use]
+pub fn collect_tags(entries: &[Entry]) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in entries.iter().flat_map(|entry| &entry.tags) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.clone());
+        }
+    }
+    tags.sort_by_key(|tag| tag.to_lowercase());
+    tags
+}
@@ -1,30 +1,64 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use totp_rs::{Algorithm, TOTP};
+use zeroize::Zeroizing;
 
 use crate::{AuthError, AuthResult};
 
+pub const PERIOD: u64 = 30;
+
+pub const DIGITS: usize = 6;
+
+/// Only SHA-1 codes with the default digits and period can be generated, anything else would
+/// show wrong codes. Settings left out are taken to be the defaults.
+#[must_use]
+pub fn is_supported(algorithm: Option<&str>, digits: Option<usize>, period: Option<u64>) -> bool {
+    algorithm.is_none_or(|algorithm| algorithm.eq_ignore_ascii_case("sha1"))
+        && digits.is_none_or(|digits| digits == DIGITS)
+        && period.is_none_or(|period| period == PERIOD)
+}
+
 /// # Errors
 pub fn generate_totp(secret: &str) -> AuthResult<(String, u64)> {
+    generate_totp_at(secret, current_time()?)
+}
+
+/// Code and seconds it stays valid for at `time`, in seconds since the Unix epoch.
+///
+/// # Errors
+pub fn generate_totp_at(secret: &str, time: u64) -> AuthResult<(String, u64)> {
     let secret = normalize_secret(secret);
     let key = decode_and_pad_secret(&secret)?;
-    let (code, remaining) = generate_totp_code(key)?;
-    Ok((code, remaining))
+    let totp = create_totp(key.to_vec())?;
+    Ok((totp.generate(time), PERIOD - (time % PERIOD)))
+}
+
+/// # Errors
+pub fn current_time() -> AuthResult<u64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| AuthError::TotpError)?
+        .as_secs())
 }
 
-fn normalize_secret(secret: &str) -> String {
+fn normalize_secret(secret: &str) -> Zeroizing<String> {
     if secret.is_empty() {
-        return String::new();
+        return Zeroizing::default();
     }
 
-    let mut secret = secret.replace(' ', "").to_uppercase();
-    while !secret.len().is_multiple_of(8) {
-        secret.push('=');
+    // Sized for the padding up front, as growing would free an unwiped copy. Only ASCII is
+    // valid base32, so uppercasing it keeps the length.
+    let mut normalized = Zeroizing::new(String::with_capacity(secret.len() + 7));
+    for c in secret.chars().filter(|&c| c != ' ') {
+        normalized.push(c.to_ascii_uppercase());
+    }
+    while !normalized.len().is_multiple_of(8) {
+        normalized.push('=');
     }
-    secret
+    normalized
 }
 
-fn decode_and_pad_secret(secret: &str) -> AuthResult<Vec<u8>> {
+fn decode_and_pad_secret(secret: &str) -> AuthResult<Zeroizing<Vec<u8>>> {
     if secret.is_empty() {
         return Err(AuthError::InvalidKey("Empty secret".to_string()));
     }
@@ -32,12 +66,12 @@ fn decode_and_pad_secret(secret: &str) -> AuthResult<Vec<u8>> {
     let decoded = base32::decode(base32::Alphabet::Rfc4648 { padding: true }, secret)
         .ok_or_else(|| AuthError::InvalidKey("Invalid base32 encoding".to_string()))?;
 
-    Ok(pad_secret_if_needed(decoded))
+    Ok(pad_secret_if_needed(Zeroizing::new(decoded)))
 }
 
-fn pad_secret_if_needed(decoded: Vec<u8>) -> Vec<u8> {
+fn pad_secret_if_needed(decoded: Zeroizing<Vec<u8>>) -> Zeroizing<Vec<u8>> {
     if decoded.len() < 16 {
-        let mut padded = vec![0; 16];
+        let mut padded = Zeroizing::new(vec![0; 16]);
         padded[..decoded.len()].copy_from_slice(&decoded);
         padded[decoded.len()..].fill(0);
         padded
@@ -46,26 +80,6 @@ fn pad_secret_if_needed(decoded: Vec<u8>) -> Vec<u8> {
     }
 }
 
-fn generate_totp_code(key: Vec<u8>) -> AuthResult<(String, u64)> {
-    let totp = create_totp(key)?;
-    let remaining = calculate_remaining_time()?;
-    let code = generate_code(&totp)?;
-
-    Ok((code, remaining))
-}
-
 fn create_totp(key: Vec<u8>) -> AuthResult<TOTP> {
-    TOTP::new(Algorithm::SHA1, 6, 1, 30, key).map_err(|_| AuthError::TotpError)
-}
-
-fn calculate_remaining_time() -> AuthResult<u64> {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|_| AuthError::TotpError)?
-        .as_secs();
-    Ok(30 - (now % 30))
-}
-
-fn generate_code(totp: &TOTP) -> AuthResult<String> {
-    totp.generate_current().map_err(|_| AuthError::TotpError)
+    TOTP::new(Algorithm::SHA1, DIGITS, 1, PERIOD, key).map_err(|_| AuthError::TotpError)
 }
@@ -1,22 +1,30 @@
 use ratatui::crossterm::event::{MouseEvent, MouseEventKind};
 
 use crate::auth_core::app::{App, InputMode};
+use crate::ui::list_view::ListView;
+
+pub fn handle_mouse_event(app: &mut App, event: MouseEvent, list: &ListView) {
+    if app.confirmation.is_some() {
+        return;
+    }
 
-pub fn handle_mouse_event(app: &mut App, event: MouseEvent) {
     match app.input_mode {
         InputMode::Adding
         | InputMode::Importing
         | InputMode::Exporting
         | InputMode::Editing
-        | InputMode::FileBrowser => {
+        | InputMode::FileBrowser
+        | InputMode::Restoring
+        | InputMode::Trash
+        | InputMode::Locked => {
             return;
         }
-        InputMode::Normal => {}
+        InputMode::Normal | InputMode::Searching => {}
     }
 
     match event.kind {
         MouseEventKind::Down(_) => handle_mouse_click(app),
-        MouseEventKind::Moved => handle_mouse_hover(app, event.row as usize),
+        MouseEventKind::Moved => handle_mouse_hover(app, list, event.row as usize),
         _ => (),
     }
 }
@@ -25,8 +33,11 @@ fn handle_mouse_click(app: &mut App) {
     app.copy_current_code();
 }
 
-fn handle_mouse_hover(app: &mut App, row: usize) {
-    if row >= 1 && row < app.entries.len() + 1 {
-        app.selected = row - 1;
+fn handle_mouse_hover(app: &mut App, list: &ListView, row: usize) {
+    if let Some(entry) = row
+        .checked_sub(list.top)
+        .and_then(|i| app.visible_entries().into_iter().nth(i + list.offset))
+    {
+        app.selected = entry.index;
     }
 }
@@ -1,13 +1,27 @@
 pub mod auth_core {
     pub mod app;
+    pub mod backup;
+    pub mod clipboard;
+    pub mod confirm;
     pub mod crypto;
     pub mod entry;
+    pub mod history;
+    pub mod import;
+    pub mod migration;
+    pub mod otpauth;
+    pub mod search;
+    pub mod sort;
+    pub mod sync;
+    pub mod tag;
     pub mod totp;
 }
 
 pub mod utils {
     pub mod cli;
+    pub mod config;
     pub mod error;
+    pub mod hardening;
+    pub mod time;
 }
 
 pub mod input {
@@ -18,11 +32,15 @@ pub mod input {
 }
 
 pub mod ui {
+    pub mod confirm;
     pub mod file_browser;
     pub mod layout;
+    pub mod list_view;
     pub mod notification;
     pub mod renderer;
+    pub mod restore;
     pub mod size;
+    pub mod trash;
 }
 
 pub use auth_core::app::App;
@@ -1,46 +1,71 @@
 use std::io::stdout;
 
 use auth::auth_core::app::App;
+use auth::auth_core::clipboard;
 use auth::input::event;
 #[cfg(unix)]
 use auth::input::root;
+use auth::ui::list_view::ListView;
 use auth::ui::renderer::draw;
-use auth::utils::cli;
 use auth::utils::error::AuthResult;
-use ratatui::crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use auth::utils::{cli, hardening};
+use ratatui::crossterm::event::{
+    DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+};
 use ratatui::crossterm::execute;
 use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 
 fn main() -> AuthResult<()> {
     let args = cli::parse_args();
 
+    if let Some(secs) = args.serve_clipboard {
+        // The helper has no UI to report hardening failures on.
+        let _ = hardening::harden_process();
+        return clipboard::serve(secs);
+    }
+
     #[cfg(unix)]
     if !args.no_root_check && root::check_root() {
         root::show_root_warning()?;
         return Ok(());
     }
 
+    let hardening_warnings = hardening::harden_process();
+
     if args.mouse {
         execute!(stdout(), EnableMouseCapture)?;
     }
+    execute!(stdout(), EnableFocusChange)?;
 
     enable_raw_mode()?;
     let mut terminal = ratatui::init();
     let mut app = App::new()?;
+    app.privacy |= args.privacy;
+    if let Some(query) = &args.query {
+        app.start_search(query);
+    }
+    if !hardening_warnings.is_empty() {
+        let warnings: Vec<String> = hardening_warnings.iter().map(ToString::to_string).collect();
+        app.add_error(&warnings.join(", "));
+    }
 
+    let mut list_view = ListView::default();
     while !app.should_quit {
-        terminal.draw(|f| draw(f, &app, args.no_size_check))?;
+        app.tick();
+        terminal.draw(|f| draw(f, &app, &mut list_view, args.no_size_check))?;
 
         if let Some(event) = event::poll_event()? {
-            app.handle_events(&event)?;
+            app.handle_events(&event, &list_view)?;
         }
     }
 
+    execute!(stdout(), DisableFocusChange)?;
     if args.mouse {
         execute!(stdout(), DisableMouseCapture)?;
     }
     disable_raw_mode()?;
 
     ratatui::restore();
-    Ok(())
+    app.save_usage();
+    app.clipboard.hand_off()
 }
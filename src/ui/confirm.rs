@@ -0,0 +1,26 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Clear, Paragraph, Wrap};
+
+use crate::auth_core::confirm::Confirmation;
+use crate::ui::layout::{POPUP_MIN_WIDTH, centered_rect_min, create_block};
+
+const CONFIRM_POPUP_MIN_HEIGHT: u16 = 7;
+
+pub fn draw_confirm_popup(frame: &mut Frame, confirmation: &Confirmation, area: Rect) {
+    let popup_area = centered_rect_min(50, 20, POPUP_MIN_WIDTH, CONFIRM_POPUP_MIN_HEIGHT, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![Line::from(confirmation.message.as_str()), Line::from("")];
+    match &confirmation.phrase {
+        Some(phrase) => {
+            lines.push(Line::from(format!("Type \"{phrase}\" to confirm:")));
+            lines.push(Line::from(format!("{}|", confirmation.input)));
+        }
+        None => lines.push(Line::from("Continue? (y/n)")),
+    }
+
+    let popup = Paragraph::new(lines)
+        .block(create_block(" Confirm ").border_style(Style::default().fg(Color::Red)))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(popup, popup_area);
+}
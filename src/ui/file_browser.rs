@@ -5,7 +5,7 @@ use ratatui::prelude::*;
 use ratatui::widgets::{Clear, List, ListItem, ListState};
 
 use crate::auth_core::app::App;
-use crate::ui::layout::{centered_rect, create_block};
+use crate::ui::layout::{POPUP_MIN_HEIGHT, POPUP_MIN_WIDTH, centered_rect_min, create_block};
 
 const FILE_BROWSER_DIR_ENV: &str = "AUTH_FILE_BROWSER_DIR";
 
@@ -203,7 +203,7 @@ pub fn draw_file_browser(
     title: &str,
     area: Rect,
 ) {
-    let popup_area = centered_rect(50, 25, area);
+    let popup_area = centered_rect_min(50, 25, POPUP_MIN_WIDTH, POPUP_MIN_HEIGHT, area);
     frame.render_widget(Clear, popup_area);
 
     let visible_entries = browser
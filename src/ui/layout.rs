@@ -2,6 +2,10 @@ use ratatui::prelude::*;
 use ratatui::style::{Color, Style};
 use ratatui::widgets::{Block, BorderType, Borders};
 
+/// Smallest popup that still shows a title and a few rows, unless the terminal is smaller.
+pub const POPUP_MIN_WIDTH: u16 = 40;
+pub const POPUP_MIN_HEIGHT: u16 = 7;
+
 #[must_use]
 pub fn create_block(title: &str) -> Block<'_> {
     Block::default()
@@ -26,6 +30,27 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     create_horizontal_layout(percent_x, vertical_layout[1])
 }
 
+/// Like `centered_rect`, but grown to at least `min_width` x `min_height` as far as `r` allows,
+/// so popups keep their content visible on small terminals.
+#[must_use]
+pub fn centered_rect_min(
+    percent_x: u16,
+    percent_y: u16,
+    min_width: u16,
+    min_height: u16,
+    r: Rect,
+) -> Rect {
+    let rect = centered_rect(percent_x, percent_y, r);
+    let width = rect.width.max(min_width).min(r.width);
+    let height = rect.height.max(min_height).min(r.height);
+    Rect {
+        x: r.x + (r.width - width) / 2,
+        y: r.y + (r.height - height) / 2,
+        width,
+        height,
+    }
+}
+
 fn create_vertical_layout(percent: u16, area: Rect) -> Vec<Rect> {
     let margin = (100 - percent) / 2;
     let constraints = [
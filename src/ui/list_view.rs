@@ -0,0 +1,22 @@
+/// Where the renderer last put the entry list, kept between frames by the main loop. Scrolling
+/// keeps the selection in view from `offset`, page keys move by `height`, and mouse hovering
+/// maps screen rows to entries from `top`.
+#[derive(Clone, Copy)]
+pub struct ListView {
+    /// Screen row of the first entry in the list
+    pub top: usize,
+    /// First visible row of the entry list
+    pub offset: usize,
+    /// Rows the entry list had on the last draw
+    pub height: usize,
+}
+
+impl Default for ListView {
+    fn default() -> Self {
+        Self {
+            top: 1,
+            offset: 0,
+            height: 1,
+        }
+    }
+}
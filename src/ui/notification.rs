@@ -1,16 +1,26 @@
 use std::time::SystemTime;
 
+use crate::auth_core::app::App;
+use crate::auth_core::backup::MirrorStatus;
+use crate::utils::time::format_age;
+
 #[must_use]
 pub fn get_notification_title(
     error_message: &Option<(String, SystemTime)>,
+    notification: &Option<(String, SystemTime)>,
     copy_notification_time: Option<SystemTime>,
+    clipboard_countdown: Option<u64>,
 ) -> String {
-    if let Some(msg) = check_notification_time(
-        error_message
-            .as_ref()
-            .map(|(msg, time)| (msg.as_str(), *time)),
-    ) {
-        return msg;
+    for message in [error_message, notification] {
+        if let Some(msg) =
+            check_notification_time(message.as_ref().map(|(msg, time)| (msg.as_str(), *time)))
+        {
+            return msg;
+        }
+    }
+
+    if let Some(secs) = clipboard_countdown {
+        return format!(" Copied! Clearing in {secs}s ");
     }
 
     if let Some(msg) = check_notification_time(copy_notification_time.map(|time| ("Copied!", time)))
@@ -29,3 +39,33 @@ fn check_notification_time(notification: Option<(&str, SystemTime)>) -> Option<S
         None
     }
 }
+
+/// Builds the backup status shown at the bottom of the main block. The outcome of the last
+/// mirror in this session wins over the on-disk timestamp, and an overdue reminder is appended.
+#[must_use]
+pub fn get_backup_status(app: &App) -> Option<String> {
+    let mirror = app.mirror.as_ref()?;
+
+    let mut status = match &app.mirror_status {
+        Some(MirrorStatus::Succeeded(time)) => format!("Mirrored {}", elapsed_age(*time)),
+        Some(MirrorStatus::Failed(time)) => format!("Mirror failed {}", elapsed_age(*time)),
+        None => mirror.last_written().map_or_else(
+            || "Never mirrored".to_string(),
+            |time| format!("Mirrored {}", elapsed_age(time)),
+        ),
+    };
+
+    if mirror.is_overdue() {
+        let reminder = mirror.days_since_written().map_or_else(
+            || "No backup written yet".to_string(),
+            |days| format!("No backup in {days} days"),
+        );
+        status = format!("{status} | {reminder}");
+    }
+
+    Some(format!(" {status} "))
+}
+
+fn elapsed_age(time: SystemTime) -> String {
+    format_age(time.elapsed().unwrap_or_default().as_secs())
+}
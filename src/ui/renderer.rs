@@ -1,15 +1,26 @@
+use std::path::Path;
+
 use ratatui::prelude::*;
-use ratatui::widgets::{Clear, Paragraph};
+use ratatui::widgets::{Block, Clear, List, ListState, Paragraph, Tabs};
 use ratatui_macros::constraints;
 
 use crate::auth_core::app::{App, InputMode};
 use crate::auth_core::entry::Entry;
+use crate::auth_core::search::SearchMatch;
+use crate::auth_core::tag::collect_tags;
+use crate::ui::confirm::draw_confirm_popup;
 use crate::ui::file_browser::draw_file_browser;
-use crate::ui::layout::{centered_rect, create_block};
-use crate::ui::notification::get_notification_title;
+use crate::ui::layout::{POPUP_MIN_WIDTH, centered_rect_min, create_block, pad_vertical};
+use crate::ui::list_view::ListView;
+use crate::ui::notification::{get_backup_status, get_notification_title};
+use crate::ui::restore::draw_restore_popup;
 use crate::ui::size::check_terminal_size;
+use crate::ui::trash::draw_trash_popup;
+
+const HELP_TEXT: &str = "a: add  E: edit  d: del  u/^r: undo/redo  i: import  e: export  b: backups  T: trash  L: lock  p: privacy  v: reveal  /: search  ↑/k: up  ↓/j: down  pgup/pgdn: page  gg/G: top/bottom  J/K: move  o: sort  enter: copy  q: quit  tab: tags/fields";
 
-const HELP_TEXT: &str = "a: add  E: edit  d: del  i: import  e: export  ↑/k: up  ↓/j: down  enter: copy  q: quit  tab: cycle fields";
+/// Shown instead of the full bindings when those do not fit the width of the help bar.
+const SHORT_HELP_TEXT: &str = "a: add  d: del  u: undo  /: search  enter: copy  q: quit";
 
 const FILE_BROWSER_HELP_TEXT: &str =
     "↑/k: up  ↓/j: down  enter: select  .: toggle hidden  q/esc: cancel";
@@ -17,57 +28,322 @@ const FILE_BROWSER_HELP_TEXT: &str =
 const EXPORT_HELP_TEXT: &str =
     "↑/k: up  ↓/j: down  enter: select  .: toggle hidden  s: save  q/esc: cancel";
 
-pub fn draw(frame: &mut Frame, app: &App, no_size_check: bool) {
+const RESTORE_HELP_TEXT: &str = "↑/k: up  ↓/j: down  enter: restore  q/esc: cancel";
+
+const TRASH_HELP_TEXT: &str =
+    "↑/k: up  ↓/j: down  enter/r: restore  x: purge  X: empty trash  q/esc: close";
+
+const LOCKED_HELP_TEXT: &str = "enter: unlock  q: quit";
+
+const CONFIRM_HELP_TEXT: &str = "y/enter: confirm  n/esc: cancel";
+
+const CONFIRM_PHRASE_HELP_TEXT: &str = "enter: confirm  esc: cancel";
+
+const SEARCH_HELP_TEXT: &str = "↑/↓: move  pgup/pgdn: page  enter: copy  esc: cancel";
+
+const LOCK_SCREEN: &[&str] = &["Vault locked", "", "Key file:"];
+
+const LOCK_SCREEN_PROMPT: &str = "Press enter to unlock with this key";
+
+const MASKED_CODE: &str = "••• •••";
+
+const ALL_TAB: &str = "All";
+
+const GAUGE_WIDTH: usize = 10;
+
+const GAUGE_FILLED: &str = "█";
+
+const GAUGE_EMPTY: &str = "░";
+
+const EXPIRY_WARNING_SECS: u64 = 10;
+
+const EXPIRY_CRITICAL_SECS: u64 = 5;
+
+const ENTRY_FIELD_LABELS: [&str; 6] = [
+    "Name:",
+    "Secret (or otpauth:// URI):",
+    "Issuer:",
+    "Account:",
+    "Tags (comma separated):",
+    "Copy template ({code}, {enter}):",
+];
+
+const ENTRY_POPUP_HEIGHT: u16 = 30;
+
+const FILE_POPUP_HEIGHT: u16 = 20;
+
+/// Rows needed to show all lines of the entry and file popups, including borders.
+const ENTRY_POPUP_MIN_HEIGHT: u16 = 14;
+
+const FILE_POPUP_MIN_HEIGHT: u16 = 4;
+
+/// Below this height the help bar is dropped in favour of entry rows.
+const HELP_MIN_HEIGHT: u16 = 12;
+
+/// Below this many rows inside the main block the tag tabs are dropped in favour of entry rows.
+const TABS_MIN_HEIGHT: u16 = 4;
+
+/// Columns after the title: separator, code, remaining time, and the gauge when it fits.
+const CODE_COLUMNS: usize = 12;
+
+/// Longer accounts, usually email addresses, end in an ellipsis.
+const MAX_ACCOUNT_WIDTH: usize = 24;
+
+const GAUGE_COLUMNS: usize = GAUGE_WIDTH + 1;
+
+/// Shortest a name is truncated to before the gauge is dropped as well.
+const MIN_NAME_WIDTH: usize = 12;
+
+/// Draws the app, recording in `list` where the entry list ended up.
+pub fn draw(frame: &mut Frame, app: &App, list: &mut ListView, no_size_check: bool) {
     let area = frame.area();
 
     if !no_size_check && check_terminal_size(frame, area) {
         return;
     }
 
-    let chunks = Layout::vertical(constraints![>=3,==3]).split(area);
-
-    draw_main_block(frame, app, chunks[0]);
-    draw_help_block(frame, app, chunks[1]);
+    if area.height < HELP_MIN_HEIGHT {
+        draw_main_block(frame, app, list, area, false);
+    } else {
+        let chunks = Layout::vertical(constraints![>=3,==3]).split(area);
+        draw_main_block(frame, app, list, chunks[0], true);
+        draw_help_block(frame, app, chunks[1]);
+    }
     draw_popups(frame, app, area);
 }
 
-fn draw_main_block(frame: &mut Frame, app: &App, area: Rect) {
-    let title = get_notification_title(&app.error_message, app.copy_notification_time);
-    let main_block = create_block(&title);
-    let entries = create_entry_lines(app);
+fn draw_main_block(frame: &mut Frame, app: &App, list: &mut ListView, area: Rect, show_help: bool) {
+    let title = get_notification_title(
+        &app.error_message,
+        &app.notification,
+        app.copy_notification_time,
+        app.clipboard.clear_countdown(),
+    );
+    let mut main_block = create_block(&title);
+    if let Some(status) = get_backup_status(app) {
+        main_block = main_block.title_bottom(Line::from(status).right_aligned());
+    }
+    if !show_help && app.input_mode == InputMode::Searching {
+        main_block = main_block.title_bottom(format!(" /{}| ", app.search_query));
+    }
+
+    if app.is_locked() {
+        draw_lock_screen(frame, main_block, area, &app.key_path());
+        return;
+    }
+
+    let mut list_area = main_block.inner(area);
+    frame.render_widget(main_block, area);
+    // Tags, filtering and ranking walk every entry, so they are worked out once per frame.
+    let tag = app.active_tag();
+    let tags = collect_tags(&app.entries);
+    if !tags.is_empty() && list_area.height >= TABS_MIN_HEIGHT {
+        let [tabs_area, rest] = Layout::vertical(constraints![==1, >=1]).areas(list_area);
+        frame.render_widget(create_tag_tabs(tag, tags), tabs_area);
+        list_area = rest;
+    }
+
+    let visible = app.entries_in_tab(tag);
+    let selected = visible.iter().position(|m| m.index == app.selected);
+    let entries = create_entry_lines(app, &visible, list_area.width.into());
+    let mut list_state = ListState::default()
+        .with_offset(list.offset)
+        .with_selected(selected);
+    list.top = list_area.y.into();
+    list.height = list_area.height.into();
+
+    frame.render_stateful_widget(List::new(entries), list_area, &mut list_state);
+    list.offset = list_state.offset();
+}
+
+/// Tab bar of every tag, after a tab for all entries.
+fn create_tag_tabs(tag: Option<&str>, tags: Vec<String>) -> Tabs<'static> {
+    let selected = tag
+        .and_then(|tag| tags.iter().position(|t| t.eq_ignore_ascii_case(tag)))
+        .map_or(0, |i| i + 1);
+    let titles = std::iter::once(ALL_TAB.to_string()).chain(tags);
+
+    Tabs::new(titles).select(selected).highlight_style(
+        Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD),
+    )
+}
 
-    let main_widget = Paragraph::new(entries)
-        .block(main_block)
-        .alignment(Alignment::Left);
+/// Names the key file unlocking reads, as pressing enter is all it takes to use it.
+fn draw_lock_screen(frame: &mut Frame, block: Block, area: Rect, key_path: &Path) {
+    let text = LOCK_SCREEN
+        .iter()
+        .map(|&s| Line::from(s))
+        .chain([
+            Line::from(key_path.display().to_string()).bold(),
+            Line::from(""),
+            Line::from(LOCK_SCREEN_PROMPT),
+        ])
+        .collect();
+    let lock_widget = Paragraph::new(pad_vertical(text, block.inner(area).height))
+        .block(block)
+        .alignment(Alignment::Center);
 
-    frame.render_widget(main_widget, area);
+    frame.render_widget(lock_widget, area);
 }
 
-fn create_entry_lines(app: &App) -> Vec<Line<'_>> {
+fn create_entry_lines<'a>(app: &'a App, visible: &[SearchMatch], width: usize) -> Vec<Line<'a>> {
     if app.entries.is_empty() {
         return Vec::new();
     }
 
-    let max_name_width = get_max_name_width(&app.entries);
-    create_formatted_lines(&app.entries, app.selected, max_name_width)
+    let max_name_width = get_max_width(&app.entries, Entry::title);
+    let max_account_width = get_max_width(&app.entries, |e| &e.account).min(MAX_ACCOUNT_WIDTH);
+    let account_columns = if max_account_width > 0 {
+        max_account_width + 2
+    } else {
+        0
+    };
+    let name_room = |columns: usize| width.saturating_sub(columns + 2);
+    let fits = |columns: usize| name_room(columns) >= max_name_width.min(MIN_NAME_WIDTH);
+
+    let show_account = account_columns > 0 && fits(CODE_COLUMNS + account_columns);
+    let mut columns = CODE_COLUMNS + if show_account { account_columns } else { 0 };
+    let show_gauge = fits(columns + GAUGE_COLUMNS);
+    if show_gauge {
+        columns += GAUGE_COLUMNS;
+    }
+    let name_width = max_name_width.min(name_room(columns)).max(1);
+    let account_width = if show_account { max_account_width } else { 0 };
+    create_formatted_lines(app, visible, name_width, account_width, show_gauge)
 }
 
-fn get_max_name_width(entries: &[Entry]) -> usize {
-    entries.iter().map(|e| e.name.len()).max().unwrap_or(0)
+fn get_max_width(entries: &[Entry], column: impl Fn(&Entry) -> &str) -> usize {
+    entries
+        .iter()
+        .map(|e| column(e).chars().count())
+        .max()
+        .unwrap_or(0)
 }
 
-fn create_formatted_lines(entries: &[Entry], selected: usize, max_width: usize) -> Vec<Line<'_>> {
-    entries
+fn create_formatted_lines<'a>(
+    app: &'a App,
+    visible: &[SearchMatch],
+    max_width: usize,
+    account_width: usize,
+    show_gauge: bool,
+) -> Vec<Line<'a>> {
+    visible
         .iter()
-        .enumerate()
-        .map(|(i, entry)| create_entry_line(i, entry, selected, max_width))
+        .map(|m| {
+            let entry = &app.entries[m.index];
+            create_entry_line(
+                entry,
+                m,
+                m.index == app.selected,
+                app.is_code_visible(m.index),
+                max_width,
+                account_width,
+                show_gauge,
+            )
+        })
         .collect()
 }
 
-fn create_entry_line(index: usize, entry: &Entry, selected: usize, max_width: usize) -> Line<'_> {
-    let style = get_line_style(index == selected);
-    let formatted_text = format_entry_text(entry, max_width);
-    Line::styled(formatted_text, style)
+fn create_entry_line<'a>(
+    entry: &'a Entry,
+    search_match: &SearchMatch,
+    is_selected: bool,
+    code_visible: bool,
+    max_width: usize,
+    account_width: usize,
+    show_gauge: bool,
+) -> Line<'a> {
+    let style = get_line_style(is_selected);
+    let (code, remaining) = entry.generate_totp_with_time();
+    let code_style = get_code_style(remaining, style);
+
+    let mut spans = highlight_matches(entry.title(), &search_match.positions, style, max_width);
+    spans.push(Span::raw(format!(
+        "{:<pad$} ",
+        "",
+        pad = max_width + 2 - entry.title().chars().count().min(max_width)
+    )));
+    if account_width > 0 {
+        let account_style = style.add_modifier(Modifier::DIM);
+        spans.extend(highlight_matches(
+            &entry.account,
+            &[],
+            account_style,
+            account_width,
+        ));
+        spans.push(Span::raw(format!(
+            "{:<pad$}",
+            "",
+            pad = account_width + 2 - entry.account.chars().count().min(account_width)
+        )));
+    }
+    spans.extend([Span::styled(
+        format!("{:>7}", if code_visible { &code } else { MASKED_CODE }),
+        code_style,
+    )]);
+    if show_gauge {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format_gauge(remaining, entry.period()),
+            code_style,
+        ));
+    }
+    spans.push(Span::raw(format!(" {remaining:>2}s")));
+    Line::from(spans).style(style)
+}
+
+/// Shifts the code from the line colour to yellow and then red as it approaches expiry.
+fn get_code_style(remaining: u64, style: Style) -> Style {
+    if remaining <= EXPIRY_CRITICAL_SECS {
+        style.fg(Color::Red)
+    } else if remaining <= EXPIRY_WARNING_SECS {
+        style.fg(Color::Yellow)
+    } else {
+        style
+    }
+}
+
+/// Bar of the time left in the entry's period, full right after a rollover.
+fn format_gauge(remaining: u64, period: u64) -> String {
+    let filled = usize::try_from((remaining * GAUGE_WIDTH as u64).div_ceil(period.max(1)))
+        .unwrap_or(GAUGE_WIDTH)
+        .min(GAUGE_WIDTH);
+    format!(
+        "{}{}",
+        GAUGE_FILLED.repeat(filled),
+        GAUGE_EMPTY.repeat(GAUGE_WIDTH - filled)
+    )
+}
+
+/// Splits a title into spans so the characters matched by a search stand out, ending it with an
+/// ellipsis when it is longer than `width`.
+fn highlight_matches(
+    name: &str,
+    positions: &[usize],
+    style: Style,
+    width: usize,
+) -> Vec<Span<'static>> {
+    let highlight = style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let truncated = name.chars().count() > width;
+    let mut spans: Vec<Span> = name
+        .chars()
+        .take(if truncated { width - 1 } else { width })
+        .enumerate()
+        .map(|(i, c)| {
+            let style = if positions.contains(&i) {
+                highlight
+            } else {
+                style
+            };
+            Span::styled(c.to_string(), style)
+        })
+        .collect();
+    if truncated {
+        spans.push(Span::styled("…", style));
+    }
+    spans
 }
 
 fn get_line_style(is_selected: bool) -> Style {
@@ -78,20 +354,15 @@ fn get_line_style(is_selected: bool) -> Style {
     })
 }
 
-fn format_entry_text(entry: &Entry, max_width: usize) -> String {
-    let (code, remaining) = entry.generate_totp_with_time();
-    format!(
-        "{:<width$} {:>6} ({:>1}s)",
-        entry.name,
-        code,
-        remaining,
-        width = max_width + 2
-    )
-}
-
 fn draw_help_block(frame: &mut Frame, app: &App, area: Rect) {
     let help_block = create_block(" Bindings ");
-    let help_text = get_help_text(app);
+    let mut help_text = get_help_text(app);
+    if help_text.width() > usize::from(area.width.saturating_sub(2))
+        && app.input_mode == InputMode::Normal
+        && app.confirmation.is_none()
+    {
+        help_text = Line::from(SHORT_HELP_TEXT);
+    }
 
     let help_widget = Paragraph::new(vec![help_text])
         .block(help_block)
@@ -101,6 +372,14 @@ fn draw_help_block(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn get_help_text(app: &App) -> Line<'static> {
+    if let Some(confirmation) = &app.confirmation {
+        return Line::from(if confirmation.phrase.is_some() {
+            CONFIRM_PHRASE_HELP_TEXT
+        } else {
+            CONFIRM_HELP_TEXT
+        });
+    }
+
     match app.input_mode {
         InputMode::FileBrowser => {
             if app.file_operation == Some(InputMode::Exporting) {
@@ -109,6 +388,10 @@ fn get_help_text(app: &App) -> Line<'static> {
                 Line::from(FILE_BROWSER_HELP_TEXT)
             }
         }
+        InputMode::Restoring => Line::from(RESTORE_HELP_TEXT),
+        InputMode::Trash => Line::from(TRASH_HELP_TEXT),
+        InputMode::Locked => Line::from(LOCKED_HELP_TEXT),
+        InputMode::Searching => Line::from(format!("/{}|  {SEARCH_HELP_TEXT}", app.search_query)),
         _ => Line::from(HELP_TEXT),
     }
 }
@@ -119,66 +402,84 @@ fn draw_popups(frame: &mut Frame, app: &App, area: Rect) {
         InputMode::Importing | InputMode::Exporting => draw_file_popup(frame, app, area),
         InputMode::Editing => draw_edit_popup(frame, app, area),
         InputMode::FileBrowser => draw_file_browser_popup(frame, app, area),
-        InputMode::Normal => {}
+        InputMode::Restoring => draw_restore_popup(frame, app, area),
+        InputMode::Trash => draw_trash_popup(frame, app, area),
+        InputMode::Normal | InputMode::Locked | InputMode::Searching => {}
+    }
+
+    if let Some(confirmation) = &app.confirmation {
+        draw_confirm_popup(frame, confirmation, area);
     }
 }
 
 fn draw_add_popup(frame: &mut Frame, app: &App, area: Rect) {
     let popup = create_entry_popup(
         " Add Entry ",
-        &app.new_entry_name,
-        &app.new_entry_secret,
+        [
+            &app.new_entry_name,
+            &app.new_entry_secret,
+            &app.new_entry_issuer,
+            &app.new_entry_account,
+            &app.new_entry_tags,
+            &app.new_entry_template,
+        ],
         app.input_field,
     );
-    render_centered_popup(frame, popup, area);
+    render_centered_popup(
+        frame,
+        popup,
+        area,
+        ENTRY_POPUP_HEIGHT,
+        ENTRY_POPUP_MIN_HEIGHT,
+    );
 }
 
 fn draw_edit_popup(frame: &mut Frame, app: &App, area: Rect) {
     let popup = create_entry_popup(
         " Edit Entry ",
-        &app.edit_entry_name,
-        &app.edit_entry_secret,
+        [
+            &app.edit_entry_name,
+            &app.edit_entry_secret,
+            &app.edit_entry_issuer,
+            &app.edit_entry_account,
+            &app.edit_entry_tags,
+            &app.edit_entry_template,
+        ],
         app.input_field,
     );
-    render_centered_popup(frame, popup, area);
+    render_centered_popup(
+        frame,
+        popup,
+        area,
+        ENTRY_POPUP_HEIGHT,
+        ENTRY_POPUP_MIN_HEIGHT,
+    );
 }
 
 fn create_entry_popup<'a>(
     title: &'a str,
-    name: &'a str,
-    secret: &'a str,
+    values: [&'a str; 6],
     input_field: usize,
 ) -> Paragraph<'a> {
-    let cursor = create_cursor_indicators(input_field);
-    let lines = create_entry_popup_lines(name, secret, &cursor);
+    let lines = create_entry_popup_lines(values, input_field);
 
     Paragraph::new(lines).block(create_block(title))
 }
 
-fn create_cursor_indicators(input_field: usize) -> (String, String) {
-    let name_cursor = if input_field == 0 { "|" } else { "" };
-    let secret_cursor = if input_field == 1 { "|" } else { "" };
-    (name_cursor.to_string(), secret_cursor.to_string())
-}
-
-fn create_entry_popup_lines<'a>(
-    name: &'a str,
-    secret: &'a str,
-    (name_cursor, secret_cursor): &(String, String),
-) -> Vec<Line<'a>> {
-    vec![
-        Line::from("Name:"),
-        Line::from(format!("{name}{name_cursor}")),
-        Line::from(""),
-        Line::from("Secret:"),
-        Line::from(format!("{secret}{secret_cursor}")),
-    ]
+fn create_entry_popup_lines(values: [&str; 6], input_field: usize) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    for (i, (label, value)) in ENTRY_FIELD_LABELS.iter().zip(values).enumerate() {
+        let cursor = if i == input_field { "|" } else { "" };
+        lines.push(Line::from(*label));
+        lines.push(Line::from(format!("{value}{cursor}")));
+    }
+    lines
 }
 
 fn draw_file_popup(frame: &mut Frame, app: &App, area: Rect) {
     let title = get_file_popup_title(&app.input_mode);
     let popup = create_file_popup(title, &app.path_input);
-    render_centered_popup(frame, popup, area);
+    render_centered_popup(frame, popup, area, FILE_POPUP_HEIGHT, FILE_POPUP_MIN_HEIGHT);
 }
 
 fn get_file_popup_title(input_mode: &InputMode) -> &'static str {
@@ -195,8 +496,14 @@ fn create_file_popup<'a>(title: &'a str, path: &'a str) -> Paragraph<'a> {
     Paragraph::new(lines).block(create_block(title))
 }
 
-fn render_centered_popup(frame: &mut Frame, popup: Paragraph, area: Rect) {
-    let popup_area = centered_rect(60, 20, area);
+fn render_centered_popup(
+    frame: &mut Frame,
+    popup: Paragraph,
+    area: Rect,
+    percent_y: u16,
+    min_height: u16,
+) {
+    let popup_area = centered_rect_min(60, percent_y, POPUP_MIN_WIDTH, min_height, area);
     frame.render_widget(Clear, popup_area);
     frame.render_widget(popup, popup_area);
 }
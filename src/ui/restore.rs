@@ -0,0 +1,47 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Clear, List, ListItem, ListState};
+
+use crate::auth_core::app::App;
+use crate::ui::layout::{POPUP_MIN_HEIGHT, POPUP_MIN_WIDTH, centered_rect_min, create_block};
+use crate::utils::time::format_timestamp;
+
+pub fn draw_restore_popup(frame: &mut Frame, app: &App, area: Rect) {
+    let popup_area = centered_rect_min(50, 25, POPUP_MIN_WIDTH, POPUP_MIN_HEIGHT, area);
+    frame.render_widget(Clear, popup_area);
+
+    let items = if app.snapshots.is_empty() {
+        vec![ListItem::new("No backups yet")]
+    } else {
+        app.snapshots
+            .iter()
+            .enumerate()
+            .map(|(i, (snapshot, count))| {
+                let item = ListItem::new(format_snapshot(snapshot.timestamp, *count));
+                if i == app.snapshot_selected {
+                    item.style(
+                        Style::default()
+                            .fg(Color::Green)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    item
+                }
+            })
+            .collect()
+    };
+
+    let list = List::new(items).block(create_block(" Restore Backup "));
+    let mut list_state = ListState::default();
+    list_state.select((!app.snapshots.is_empty()).then_some(app.snapshot_selected));
+
+    frame.render_stateful_widget(list, popup_area, &mut list_state);
+}
+
+fn format_snapshot(timestamp: u64, count: Option<usize>) -> String {
+    let count = match count {
+        Some(1) => "1 entry".to_string(),
+        Some(n) => format!("{n} entries"),
+        None => "unreadable".to_string(),
+    };
+    format!("{} UTC  {count}", format_timestamp(timestamp / 1000))
+}
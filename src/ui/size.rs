@@ -3,8 +3,9 @@ use ratatui::widgets::Paragraph;
 
 use crate::ui::layout::pad_vertical;
 
-const MIN_WIDTH: u16 = 110;
-const MIN_HEIGHT: u16 = 30;
+/// Smallest area the compact view fits in: a truncated name and the code, one entry per row.
+const MIN_WIDTH: u16 = 30;
+const MIN_HEIGHT: u16 = 5;
 
 const SIZE_WARNING: &[&str] = &[
     "Terminal size too small:",
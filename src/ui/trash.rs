@@ -0,0 +1,42 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Clear, List, ListItem, ListState};
+
+use crate::auth_core::app::App;
+use crate::ui::layout::{POPUP_MIN_HEIGHT, POPUP_MIN_WIDTH, centered_rect_min, create_block};
+use crate::utils::time::format_timestamp;
+
+pub fn draw_trash_popup(frame: &mut Frame, app: &App, area: Rect) {
+    let popup_area = centered_rect_min(50, 25, POPUP_MIN_WIDTH, POPUP_MIN_HEIGHT, area);
+    frame.render_widget(Clear, popup_area);
+
+    let items = if app.trash.is_empty() {
+        vec![ListItem::new("Trash is empty")]
+    } else {
+        app.trash
+            .iter()
+            .enumerate()
+            .map(|(i, trashed)| {
+                let item = ListItem::new(format!(
+                    "{}  deleted {} UTC",
+                    trashed.entry.name,
+                    format_timestamp(trashed.deleted_at)
+                ));
+                if i == app.trash_selected {
+                    item.style(
+                        Style::default()
+                            .fg(Color::Green)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    item
+                }
+            })
+            .collect()
+    };
+
+    let list = List::new(items).block(create_block(" Trash "));
+    let mut list_state = ListState::default();
+    list_state.select((!app.trash.is_empty()).then_some(app.trash_selected));
+
+    frame.render_stateful_widget(list, popup_area, &mut list_state);
+}
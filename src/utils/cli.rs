@@ -1,5 +1,6 @@
 use clap::Parser;
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 pub struct Args {
@@ -15,6 +16,17 @@ pub struct Args {
     /// Enable mouse support
     #[arg(long, short = 'm')]
     pub mouse: bool,
+
+    /// Mask codes until they are revealed
+    #[arg(long, short = 'p')]
+    pub privacy: bool,
+
+    /// Start searching the entries for this query
+    pub query: Option<String>,
+
+    /// Serve a code read from stdin on the clipboard, used internally after quitting
+    #[arg(long, hide = true, value_name = "SECS")]
+    pub serve_clipboard: Option<u64>,
 }
 
 #[must_use]
@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::auth_core::clipboard::ClipboardBackend;
+use crate::{AuthError, AuthResult};
+
+const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Config {
+    pub backup: BackupConfig,
+    pub lock: LockConfig,
+    pub privacy: PrivacyConfig,
+    pub clipboard: ClipboardConfig,
+    pub trash: TrashConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct TrashConfig {
+    /// Days a deleted entry stays in the trash before it is purged, `0` keeps it until purged
+    pub retention_days: u64,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Number of vault generations kept in the `backups` directory, `0` disables them
+    pub retention: usize,
+    /// Directory that receives an encrypted copy of the vault after every save
+    pub mirror_dir: Option<PathBuf>,
+    /// Days without a successful mirror before a reminder is shown, `0` disables it
+    pub reminder_days: u64,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            retention: 10,
+            mirror_dir: None,
+            reminder_days: 7,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct LockConfig {
+    /// Seconds without input before the vault is locked, `0` disables auto-lock
+    pub idle_timeout_secs: u64,
+    /// Lock the vault as well when the terminal loses focus, instead of only hiding codes
+    pub on_focus_loss: bool,
+}
+
+impl Default for LockConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout_secs: 300,
+            on_focus_loss: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    /// Start with codes masked, same as `--privacy`
+    pub enabled: bool,
+    /// Seconds a revealed code stays visible in privacy mode
+    pub reveal_secs: u64,
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            reveal_secs: 5,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// Seconds after which a copied code is cleared from the clipboard, `0` keeps it
+    pub clear_after_secs: u64,
+    /// Keep serving a copied code from a background helper after quitting (X11 and Wayland)
+    pub keep_after_exit: bool,
+    pub backend: ClipboardBackend,
+    /// Below this many seconds left, a copy applies `rollover` instead, `0` disables it
+    pub rollover_threshold_secs: u64,
+    pub rollover: RolloverAction,
+}
+
+/// What copying does when the current code is about to expire.
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RolloverAction {
+    /// Copy the code of the next period right away
+    #[default]
+    Next,
+    /// Copy once the next period has started
+    Wait,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            clear_after_secs: 30,
+            keep_after_exit: true,
+            backend: ClipboardBackend::Auto,
+            rollover_threshold_secs: 3,
+            rollover: RolloverAction::Next,
+        }
+    }
+}
+
+impl Config {
+    /// Reads `config.toml` from the auth directory, falling back to defaults when it is missing.
+    ///
+    /// # Errors
+    pub fn load(auth_dir: &Path) -> AuthResult<Self> {
+        let path = auth_dir.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path).map_err(|_| AuthError::ReadError)?;
+        toml::from_str(&contents).map_err(|_| AuthError::ConfigError)
+    }
+}
@@ -9,6 +9,9 @@ pub enum AuthError {
     #[error("Failed to parse entries")]
     ParseError,
 
+    #[error("Vault version {0} was written by a newer version of auth")]
+    UnsupportedVersion(u32),
+
     #[error("Failed to read file")]
     ReadError,
 
@@ -33,6 +36,9 @@ pub enum AuthError {
     #[error("Failed to generate TOTP code")]
     TotpError,
 
+    #[error("Only SHA1 codes with 6 digits and a 30 second period are supported")]
+    UnsupportedTotpError,
+
     #[error("Failed to decrypt entries")]
     DecryptError,
 
@@ -48,6 +54,18 @@ pub enum AuthError {
     #[error("Empty entries are not allowed")]
     EmptyEntryError,
 
+    #[error("Copy template must contain {{code}}")]
+    TemplateError,
+
+    #[error("Confirmation text does not match")]
+    ConfirmationError,
+
+    #[error("Entry was changed or removed in the meantime")]
+    EntryChangedError,
+
+    #[error("Entries can only be moved in manual order")]
+    SortedMoveError,
+
     #[error("Path points to a directory")]
     DirectoryError,
 
@@ -60,8 +78,41 @@ pub enum AuthError {
     #[error("No entries to export")]
     EmptyExportError,
 
-    #[error("File must have .toml extension")]
-    TomlExtError,
+    #[error("File must have .toml or .json extension")]
+    ImportExtError,
+
+    #[error("Unrecognized import format, expected an Aegis or andOTP export")]
+    ImportFormatError,
+
+    #[error("Encrypted exports are not supported, export without encryption")]
+    EncryptedImportError,
+
+    #[error("Failed to parse config.toml")]
+    ConfigError,
+
+    #[error("Failed to back up entries")]
+    BackupError,
+
+    #[error("Failed to restore backup")]
+    RestoreError,
+
+    #[error("Failed to write backup mirror")]
+    MirrorError,
+
+    #[error("Failed to disable core dumps")]
+    CoreDumpError,
+
+    #[error("Failed to disable ptrace attachment")]
+    PtraceError,
+
+    #[error("Failed to lock the decryption buffer in memory")]
+    MemoryLockError,
+
+    #[error("Failed to lock auth directory")]
+    LockError,
+
+    #[error("Vault is locked")]
+    VaultLockedError,
 
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
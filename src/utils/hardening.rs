@@ -0,0 +1,131 @@
+use std::io::{self, Write};
+use std::ops::Deref;
+
+#[cfg(target_os = "linux")]
+use nix::sys::{mman, prctl, resource};
+use zeroize::Zeroize;
+
+use crate::AuthError;
+
+/// Room a typed field starts with, enough for most secrets to never need to grow.
+const MIN_FIELD_CAPACITY: usize = 64;
+
+/// Applies best-effort protections against secrets leaking out of the process: no core dumps
+/// and no ptrace attachment by other processes of the same user. Returns an error for every
+/// step that failed, the app keeps running either way.
+#[must_use]
+pub fn harden_process() -> Vec<AuthError> {
+    let mut warnings = Vec::new();
+
+    #[cfg(target_os = "linux")]
+    {
+        if resource::setrlimit(resource::Resource::RLIMIT_CORE, 0, 0).is_err() {
+            warnings.push(AuthError::CoreDumpError);
+        }
+        if prctl::set_dumpable(false).is_err() {
+            warnings.push(AuthError::PtraceError);
+        }
+    }
+
+    warnings
+}
+
+/// Fixed-capacity buffer for decrypted data. On Linux its pages are `mlock`ed so the plaintext
+/// never reaches swap, and it refuses to grow so no unlocked copy is left behind by a
+/// reallocation. The contents are zeroized before the pages are unlocked on drop.
+///
+/// Only this buffer is locked: the entries parsed from it live in ordinary allocations, which
+/// are zeroized when dropped but may be swapped out in the meantime.
+pub struct LockedBuffer {
+    data: Vec<u8>,
+    lock_failed: bool,
+}
+
+impl LockedBuffer {
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        let data = Vec::with_capacity(capacity);
+        let lock_failed = !lock_memory(&data);
+        Self { data, lock_failed }
+    }
+
+    /// Whether `mlock` was attempted and refused, e.g. because `RLIMIT_MEMLOCK` is too low.
+    #[must_use]
+    pub fn lock_failed(&self) -> bool {
+        self.lock_failed
+    }
+}
+
+impl Deref for LockedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl Write for LockedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.len() > self.data.capacity() - self.data.len() {
+            return Err(io::ErrorKind::OutOfMemory.into());
+        }
+        self.data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LockedBuffer {
+    fn drop(&mut self) {
+        self.data.zeroize();
+        unlock_memory(&self.data);
+    }
+}
+
+/// Appends `c` to a field that may hold a secret. `String::push` frees the old buffer without
+/// wiping it when it has to grow, so here the contents move to a larger buffer and the old one
+/// is zeroized before it is freed.
+pub fn push_wiped(field: &mut String, c: char) {
+    if field.len() + c.len_utf8() > field.capacity() {
+        let mut grown = String::with_capacity((field.capacity() * 2).max(MIN_FIELD_CAPACITY));
+        grown.push_str(field);
+        field.zeroize();
+        *field = grown;
+    }
+    field.push(c);
+}
+
+#[cfg(target_os = "linux")]
+fn lock_memory(data: &Vec<u8>) -> bool {
+    let Some(addr) = std::ptr::NonNull::new(data.as_ptr().cast_mut().cast()) else {
+        return false;
+    };
+    if data.capacity() == 0 {
+        return true;
+    }
+    // SAFETY: the range is the allocation owned by `data`, which outlives the lock.
+    unsafe { mman::mlock(addr, data.capacity()) }.is_ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn lock_memory(_data: &Vec<u8>) -> bool {
+    true
+}
+
+#[cfg(target_os = "linux")]
+fn unlock_memory(data: &Vec<u8>) {
+    let Some(addr) = std::ptr::NonNull::new(data.as_ptr().cast_mut().cast()) else {
+        return;
+    };
+    if data.capacity() == 0 {
+        return;
+    }
+    // SAFETY: same allocation that was passed to `mlock`, still owned by `data`.
+    unsafe { mman::munlock(addr, data.capacity()) }.ok();
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unlock_memory(_data: &Vec<u8>) {}
@@ -0,0 +1,79 @@
+use std::env;
+use std::fs;
+
+use auth::auth_core::backup::{Backups, Mirror};
+use auth::utils::time::format_timestamp;
+
+#[test]
+fn test_snapshot_retention() {
+    let auth_dir = env::temp_dir().join("test_backup_retention");
+    fs::create_dir_all(&auth_dir).unwrap();
+    let entries_path = auth_dir.join("entries.toml");
+    let backups = Backups::new(&auth_dir, 2);
+
+    for generation in 0..4 {
+        fs::write(&entries_path, format!("generation {generation}")).unwrap();
+        backups.snapshot(&entries_path).unwrap();
+    }
+
+    let snapshots = backups.list();
+    assert_eq!(snapshots.len(), 2);
+    assert!(snapshots[0].timestamp > snapshots[1].timestamp);
+    assert_eq!(
+        fs::read_to_string(&snapshots[0].path).unwrap(),
+        "generation 3"
+    );
+    assert_eq!(
+        fs::read_to_string(&snapshots[1].path).unwrap(),
+        "generation 2"
+    );
+
+    fs::remove_dir_all(&auth_dir).ok();
+}
+
+#[test]
+fn test_snapshot_disabled() {
+    let auth_dir = env::temp_dir().join("test_backup_disabled");
+    fs::create_dir_all(&auth_dir).unwrap();
+    let entries_path = auth_dir.join("entries.toml");
+    fs::write(&entries_path, "data").unwrap();
+
+    let backups = Backups::new(&auth_dir, 0);
+    backups.snapshot(&entries_path).unwrap();
+    assert!(backups.list().is_empty());
+
+    fs::remove_dir_all(&auth_dir).ok();
+}
+
+#[test]
+fn test_mirror_write() {
+    let mirror_dir = env::temp_dir().join("test_backup_mirror");
+    fs::create_dir_all(&mirror_dir).unwrap();
+
+    let mut mirror = Mirror::new(mirror_dir.clone(), 7);
+    assert!(mirror.is_overdue());
+
+    mirror.write(b"encrypted").unwrap();
+    assert_eq!(
+        fs::read(mirror_dir.join("entries.toml")).unwrap(),
+        b"encrypted"
+    );
+    assert_eq!(mirror.days_since_written(), Some(0));
+    assert!(!mirror.is_overdue());
+
+    fs::remove_dir_all(&mirror_dir).ok();
+}
+
+#[test]
+fn test_mirror_missing_dir() {
+    let mut mirror = Mirror::new(env::temp_dir().join("nonexistent_mirror_123456789"), 0);
+    assert!(mirror.write(b"encrypted").is_err());
+    assert!(!mirror.is_overdue());
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+    assert_eq!(format_timestamp(1_751_284_800), "2025-06-30 12:00");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+}
@@ -0,0 +1,114 @@
+//! Setup shared by the integration tests. Each test crate uses only part of it.
+#![allow(dead_code)]
+
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use auth::auth_core::app::App;
+use auth::auth_core::entry::{Entry, new_entry_id};
+use auth::ui::list_view::ListView;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+pub const SECRET: &str = "JBSWY3DPEHPK3PXP";
+
+/// App on a fresh vault in its own temporary directory, which is removed again on drop. Tests
+/// using it are marked `#[serial]`, as they share the clipboard and the environment.
+pub struct TestApp {
+    app: App,
+    dir: PathBuf,
+}
+
+impl TestApp {
+    pub fn new(name: &str) -> Self {
+        let dir = Self::fresh_dir(name);
+        let app = App::open(&dir).unwrap();
+        Self { app, dir }
+    }
+
+    /// Like `new`, with `config` written to the vault's `config.toml` first.
+    pub fn with_config(name: &str, config: &str) -> Self {
+        let dir = Self::fresh_dir(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.toml"), config).unwrap();
+        let app = App::open(&dir).unwrap();
+        Self { app, dir }
+    }
+
+    fn fresh_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("auth_{name}_{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Another instance on the same vault, as a second running app would be.
+    pub fn open_again(&self) -> App {
+        App::open(&self.dir).unwrap()
+    }
+}
+
+impl Deref for TestApp {
+    type Target = App;
+
+    fn deref(&self) -> &App {
+        &self.app
+    }
+}
+
+impl DerefMut for TestApp {
+    fn deref_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+/// Entry with a fresh id and a valid secret.
+pub fn entry(name: &str) -> Entry {
+    Entry {
+        id: new_entry_id(),
+        name: name.to_string(),
+        secret: SECRET.to_string().into(),
+        ..Entry::default()
+    }
+}
+
+/// Adds an entry through the add popup's fields.
+pub fn add(app: &mut App, name: &str) {
+    app.new_entry_name = name.to_string();
+    app.new_entry_secret = SECRET.to_string().into();
+    app.add_entry();
+}
+
+pub fn press(app: &mut App, code: KeyCode) {
+    press_in(app, code, &ListView::default());
+}
+
+/// Presses `code` with the entry list laid out as `list`.
+pub fn press_in(app: &mut App, code: KeyCode, list: &ListView) {
+    app.handle_events(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)), list)
+        .unwrap();
+}
+
+/// Sends any other terminal event, e.g. a focus change.
+pub fn send(app: &mut App, event: &Event) {
+    app.handle_events(event, &ListView::default()).unwrap();
+}
+
+pub fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+}
+
+pub fn names(app: &App) -> Vec<&str> {
+    app.entries.iter().map(|e| e.name.as_str()).collect()
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{TestApp, add, press, type_text};
+use ratatui::crossterm::event::KeyCode;
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn test_deletions_require_confirmation() {
+    let mut app = TestApp::new("confirm");
+    for name in ["first", "second"] {
+        add(&mut app, name);
+    }
+    app.selected = 0;
+
+    press(&mut app, KeyCode::Char('D'));
+    type_text(&mut app, "delete");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.confirmation.is_some());
+    press(&mut app, KeyCode::Esc);
+    assert!(app.confirmation.is_none());
+    assert_eq!(app.entries.len(), 2);
+
+    press(&mut app, KeyCode::Char('d'));
+    type_text(&mut app, "first");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.confirmation.is_none());
+    assert_eq!(app.entries.len(), 1);
+    assert_eq!(app.entries[0].name, "second");
+
+    press(&mut app, KeyCode::Char('D'));
+    type_text(&mut app, "delete all");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.entries.is_empty());
+}
+
+#[test]
+#[serial]
+fn test_confirmed_deletion_follows_the_entry() {
+    let mut app = TestApp::new("confirm_identity");
+    for name in ["first", "second"] {
+        add(&mut app, name);
+    }
+
+    // A reload reordering the list while the popup is open must not redirect the deletion.
+    app.selected = 0;
+    press(&mut app, KeyCode::Char('d'));
+    app.entries.swap(0, 1);
+    type_text(&mut app, "first");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.entries.len(), 1);
+    assert_eq!(app.entries[0].name, "second");
+
+    // Nor go ahead once the entry was renamed.
+    press(&mut app, KeyCode::Char('d'));
+    app.entries[0].name = "renamed".to_string();
+    type_text(&mut app, "second");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.entries.len(), 1);
+    assert!(app.error_message.is_some());
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use std::thread;
+use std::time::Duration;
+
+use auth::auth_core::app::App;
+use common::{TestApp, add, press};
+use ratatui::crossterm::event::KeyCode;
+use serial_test::serial;
+
+/// OSC 52 always succeeds, unlike a system clipboard that may be missing where tests run.
+fn rollover_config(threshold: u64, rollover: &str) -> String {
+    format!(
+        "[clipboard]\nbackend = \"osc52\"\nrollover_threshold_secs = {threshold}\nrollover = \"{rollover}\"\n"
+    )
+}
+
+fn notification(app: &App) -> &str {
+    app.notification
+        .as_ref()
+        .map_or("", |(message, _)| message.as_str())
+}
+
+#[test]
+#[serial]
+fn test_copy_outside_rollover_threshold() {
+    let mut app = TestApp::with_config("copy_now", &rollover_config(0, "wait"));
+    add(&mut app, "entry");
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.entries[0].use_count, 1);
+    assert!(app.notification.is_none());
+}
+
+#[test]
+#[serial]
+fn test_rollover_copies_next_code() {
+    // Every code is within 30 seconds of expiring, so this always rolls over.
+    let mut app = TestApp::with_config("copy_next", &rollover_config(30, "next"));
+    add(&mut app, "entry");
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.entries[0].use_count, 1);
+    assert!(notification(&app).starts_with("Copied next code, valid in"));
+}
+
+#[test]
+#[serial]
+fn test_rollover_waits_for_next_period() {
+    let mut app = TestApp::with_config("copy_wait", &rollover_config(30, "wait"));
+    for name in ["first", "second"] {
+        add(&mut app, name);
+    }
+    app.selected = 1;
+
+    press(&mut app, KeyCode::Enter);
+    assert!(notification(&app).starts_with("Copying new code in"));
+    assert_eq!(app.entries[1].use_count, 0);
+
+    // The deferred copy follows the entry even when the list changes in the meantime.
+    app.entries.swap(0, 1);
+    let (_, remaining) = app.entries[0].generate_totp_with_time();
+    thread::sleep(Duration::from_secs(remaining));
+    app.tick();
+    assert_eq!(notification(&app), "Copied new code");
+    assert_eq!(app.entries[0].name, "second");
+    assert_eq!(app.entries[0].use_count, 1);
+    assert_eq!(app.entries[1].use_count, 0);
+}
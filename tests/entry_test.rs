@@ -4,7 +4,8 @@ use auth::auth_core::entry::Entry;
 fn test_valid_entry_totp() {
     let entry = Entry {
         name: "Test Entry".to_string(),
-        secret: "JBSWY3DPEHPK3PXP".to_string(),
+        secret: "JBSWY3DPEHPK3PXP".to_string().into(),
+        ..Entry::default()
     };
 
     let (code, remaining) = entry.generate_totp_with_time();
@@ -17,10 +18,41 @@ fn test_valid_entry_totp() {
 fn test_invalid_entry_totp() {
     let entry = Entry {
         name: "Invalid Entry".to_string(),
-        secret: "INVALID!SECRET".to_string(),
+        secret: "INVALID!SECRET".to_string().into(),
+        ..Entry::default()
     };
 
     let (code, remaining) = entry.generate_totp_with_time();
     assert_eq!(code, "Invalid");
     assert_eq!(remaining, 0);
 }
+
+#[test]
+fn test_copy_template() {
+    let mut entry = Entry {
+        name: "VPN".to_string(),
+        secret: "JBSWY3DPEHPK3PXP".to_string().into(),
+        ..Entry::default()
+    };
+    assert_eq!(*entry.format_code("123456"), "123456");
+
+    entry.template = "1234{code}".to_string().into();
+    assert_eq!(*entry.format_code("123456"), "1234123456");
+
+    entry.template = "{code}{enter}".to_string().into();
+    assert_eq!(*entry.format_code("123456"), "123456\n");
+}
+
+#[test]
+fn test_template_is_omitted_when_empty() {
+    let entry = Entry {
+        name: "Test Entry".to_string(),
+        secret: "JBSWY3DPEHPK3PXP".to_string().into(),
+        ..Entry::default()
+    };
+    let serialized = toml::to_string(&entry).unwrap();
+    assert!(!serialized.contains("template"));
+
+    let parsed: Entry = toml::from_str(&serialized).unwrap();
+    assert!(parsed == entry);
+}
@@ -0,0 +1,19 @@
+use auth::utils::hardening::push_wiped;
+
+#[test]
+fn test_push_wiped_grows_without_losing_contents() {
+    let secret = "JBSWY3DPEHPK3PXP".repeat(10);
+    let mut field = String::new();
+    for c in secret.chars() {
+        push_wiped(&mut field, c);
+    }
+    assert_eq!(field, secret);
+
+    // Growing moves the field to a new buffer instead of reallocating in place.
+    let mut field = String::with_capacity(1);
+    push_wiped(&mut field, 'a');
+    let buffer = field.as_ptr();
+    push_wiped(&mut field, 'b');
+    assert_ne!(field.as_ptr(), buffer);
+    assert_eq!(field, "ab");
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use auth::auth_core::app::InputMode;
+use auth::auth_core::entry::Entry;
+use auth::auth_core::history::{HISTORY_LIMIT, History};
+use common::{TestApp, add, entry, names, press};
+use ratatui::crossterm::event::KeyCode;
+use serial_test::serial;
+
+#[test]
+fn test_history_undo_redo() {
+    let mut history = History::<Vec<Entry>>::default();
+    let before = vec![entry("a")];
+    let after = vec![before[0].clone(), entry("b")];
+    history.record("add \"b\"".to_string(), before.clone(), after.clone());
+
+    let change = history.undo().unwrap();
+    assert_eq!(change.label, "add \"b\"");
+    assert!(change.before == before && change.after == after);
+    assert!(history.undo().is_none());
+
+    let change = history.redo().unwrap();
+    assert!(change.after == after);
+    assert!(history.redo().is_none());
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let mut history = History::<Vec<Entry>>::default();
+    for i in 0..HISTORY_LIMIT + 5 {
+        history.record(i.to_string(), Vec::new(), Vec::new());
+    }
+
+    let mut undone = 0;
+    while history.undo().is_some() {
+        undone += 1;
+    }
+    assert_eq!(undone, HISTORY_LIMIT);
+}
+
+#[test]
+#[serial]
+fn test_undo_persists() {
+    let mut app = TestApp::new("undo");
+    add(&mut app, "entry");
+    app.delete_all_entries();
+    assert!(app.entries.is_empty());
+
+    app.undo();
+    assert_eq!(app.entries.len(), 1);
+    app.redo();
+    assert!(app.entries.is_empty());
+    app.undo();
+
+    let reloaded = app.open_again();
+    assert_eq!(names(&reloaded), ["entry"]);
+}
+
+#[test]
+#[serial]
+fn test_undo_keeps_changes_from_other_instances() {
+    let mut first = TestApp::new("undo_instances");
+    add(&mut first, "first");
+    let mut second = first.open_again();
+    add(&mut second, "second");
+
+    // Saving picks up the other instance's entry, which undoing must not drop again.
+    add(&mut first, "third");
+    first.undo();
+    first.undo();
+    assert_eq!(names(&first), ["second"]);
+
+    first.redo();
+    assert_eq!(names(&first), ["second", "first"]);
+}
+
+#[test]
+#[serial]
+fn test_unchanged_edit_is_not_recorded() {
+    let mut app = TestApp::new("undo_noop");
+    add(&mut app, "entry");
+    press(&mut app, KeyCode::Char('E'));
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Enter);
+    assert!(app.input_mode == InputMode::Normal);
+
+    // The edit changed nothing, so undo goes straight to the add.
+    app.undo();
+    assert!(app.entries.is_empty());
+}
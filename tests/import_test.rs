@@ -0,0 +1,114 @@
+use auth::AuthError;
+use auth::auth_core::import::parse_json_export;
+
+#[test]
+fn test_aegis_import() {
+    let export = r#"{
+        "version": 1,
+        "header": { "slots": null, "params": null },
+        "db": {
+            "version": 3,
+            "entries": [
+                {
+                    "type": "totp",
+                    "uuid": "1",
+                    "name": "octocat",
+                    "issuer": "GitHub",
+                    "groups": ["g-work"],
+                    "info": { "secret": "JBSWY3DPEHPK3PXP", "algo": "SHA1", "digits": 6, "period": 30 }
+                },
+                {
+                    "type": "totp",
+                    "uuid": "2",
+                    "name": "me@example.com",
+                    "issuer": "",
+                    "group": "Personal",
+                    "info": { "secret": "JBSWY3DPEHPK3PXP", "algo": "SHA1", "digits": 6, "period": 30 }
+                },
+                {
+                    "type": "hotp",
+                    "uuid": "3",
+                    "name": "counter",
+                    "issuer": "VPN",
+                    "info": { "secret": "JBSWY3DPEHPK3PXP", "algo": "SHA1", "digits": 6, "counter": 0 }
+                },
+                {
+                    "type": "totp",
+                    "uuid": "4",
+                    "name": "long",
+                    "issuer": "Bank",
+                    "info": { "secret": "JBSWY3DPEHPK3PXP", "algo": "SHA256", "digits": 8, "period": 30 }
+                }
+            ],
+            "groups": [{ "uuid": "g-work", "name": "Work" }]
+        }
+    }"#;
+
+    let imported = parse_json_export(export).unwrap();
+    assert_eq!(imported.skipped, 2);
+    assert_eq!(imported.entries.len(), 2);
+
+    let github = &imported.entries[0];
+    assert_eq!(github.name, "GitHub");
+    assert_eq!(github.issuer, "GitHub");
+    assert_eq!(github.account, "octocat");
+    assert_eq!(github.tags, vec!["Work"]);
+    assert_eq!(*github.secret, "JBSWY3DPEHPK3PXP");
+
+    let email = &imported.entries[1];
+    assert_eq!(email.name, "me@example.com");
+    assert_eq!(email.issuer, "");
+    assert_eq!(email.tags, vec!["Personal"]);
+}
+
+#[test]
+fn test_andotp_import() {
+    let export = r#"[
+        {
+            "secret": "JBSWY3DPEHPK3PXP",
+            "issuer": "",
+            "label": "GitHub:octocat",
+            "digits": 6,
+            "type": "TOTP",
+            "algorithm": "SHA1",
+            "thumbnail": "Default",
+            "last_used": 0,
+            "used_frequency": 0,
+            "period": 30,
+            "tags": ["work", "code"]
+        },
+        {
+            "secret": "JBSWY3DPEHPK3PXP",
+            "issuer": "Steam",
+            "label": "gamer",
+            "digits": 5,
+            "type": "STEAM",
+            "algorithm": "SHA1",
+            "period": 30,
+            "tags": []
+        }
+    ]"#;
+
+    let imported = parse_json_export(export).unwrap();
+    assert_eq!(imported.skipped, 1);
+    assert_eq!(imported.entries.len(), 1);
+    assert_eq!(imported.entries[0].issuer, "GitHub");
+    assert_eq!(imported.entries[0].account, "octocat");
+    assert_eq!(imported.entries[0].tags, vec!["work", "code"]);
+}
+
+#[test]
+fn test_unsupported_json_exports() {
+    assert!(matches!(
+        parse_json_export(r#"{ "version": 1, "header": {}, "db": "ZW5jcnlwdGVk" }"#),
+        Err(AuthError::EncryptedImportError)
+    ));
+    assert!(matches!(
+        parse_json_export(r#"{ "entries": [] }"#),
+        Err(AuthError::ImportFormatError)
+    ));
+    assert!(matches!(
+        parse_json_export("not json"),
+        Err(AuthError::ParseError)
+    ));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use auth::auth_core::app::InputMode;
+use common::{TestApp, add, press, send};
+use ratatui::crossterm::event::{Event, KeyCode};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn test_lock_and_unlock() {
+    let mut app = TestApp::new("lock_unlock");
+    add(&mut app, "entry");
+
+    app.lock();
+    assert!(app.is_locked());
+    assert!(app.input_mode == InputMode::Locked);
+    assert!(app.entries.is_empty());
+    assert!(app.save_entries().is_err());
+    assert_eq!(app.key_path(), app.dir().join("key"));
+
+    app.unlock();
+    assert!(!app.is_locked());
+    assert!(app.input_mode == InputMode::Normal);
+    assert_eq!(app.entries.len(), 1);
+    assert_eq!(app.entries[0].name, "entry");
+}
+
+#[test]
+#[serial]
+fn test_focus_loss_hides_codes() {
+    let mut app = TestApp::new("lock_focus");
+    add(&mut app, "entry");
+
+    send(&mut app, &Event::FocusLost);
+    assert!(!app.is_code_visible(0));
+    assert!(!app.is_locked());
+    send(&mut app, &Event::FocusGained);
+    assert!(app.is_code_visible(0));
+
+    // A revealed code does not come back with the focus.
+    press(&mut app, KeyCode::Char('p'));
+    press(&mut app, KeyCode::Char('v'));
+    send(&mut app, &Event::FocusLost);
+    send(&mut app, &Event::FocusGained);
+    assert!(!app.is_code_visible(0));
+}
+
+#[test]
+#[serial]
+fn test_focus_loss_locks_when_configured() {
+    let mut app = TestApp::with_config("lock_focus_loss", "[lock]\non_focus_loss = true\n");
+    add(&mut app, "entry");
+
+    send(&mut app, &Event::FocusLost);
+    assert!(app.is_locked());
+    assert!(app.entries.is_empty());
+}
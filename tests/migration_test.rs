@@ -0,0 +1,77 @@
+use auth::AuthError;
+use auth::auth_core::entry::Entries;
+use auth::auth_core::migration::{VAULT_VERSION, parse_vault};
+
+const LEGACY_VAULT: &str = r#"
+[[entries]]
+name = "GitHub:octocat"
+secret = "JBSWY3DPEHPK3PXP"
+
+[[entries]]
+name = "Email"
+secret = "JBSWY3DPEHPK3PXP"
+
+[[trash]]
+deleted_at = 1700000000
+
+[trash.entry]
+name = "AWS:root"
+secret = "JBSWY3DPEHPK3PXP"
+"#;
+
+#[test]
+fn test_unversioned_vault_is_migrated() {
+    let vault = parse_vault(LEGACY_VAULT).unwrap();
+
+    assert_eq!(vault.version, VAULT_VERSION);
+    assert_eq!(vault.entries[0].name, "GitHub:octocat");
+    assert_eq!(vault.entries[0].issuer, "GitHub");
+    assert_eq!(vault.entries[0].account, "octocat");
+    assert_eq!(vault.entries[1].issuer, "");
+    assert_eq!(vault.entries[1].account, "");
+    assert_eq!(vault.trash[0].entry.issuer, "AWS");
+    assert_eq!(vault.trash[0].entry.account, "root");
+
+    let ids = [
+        vault.entries[0].id,
+        vault.entries[1].id,
+        vault.trash[0].entry.id,
+    ];
+    assert!(ids.iter().all(|&id| id != 0));
+    assert!(ids[0] != ids[1] && ids[1] != ids[2] && ids[0] != ids[2]);
+    assert_eq!(parse_vault(LEGACY_VAULT).unwrap().entries[0].id, ids[0]);
+}
+
+#[test]
+fn test_current_vault_round_trips() {
+    let mut vault = parse_vault(LEGACY_VAULT).unwrap();
+    vault.entries[0].issuer = "Renamed".to_string();
+    let serialized = toml::to_string(&vault).unwrap();
+    assert!(serialized.starts_with(&format!("version = {VAULT_VERSION}")));
+
+    let reparsed = parse_vault(&serialized).unwrap();
+    assert_eq!(reparsed.entries[0].issuer, "Renamed");
+    assert_eq!(toml::to_string(&reparsed).unwrap(), serialized);
+    assert_eq!(Entries::default().version, VAULT_VERSION);
+}
+
+#[test]
+fn test_newer_vault_is_rejected() {
+    let newer = format!("version = {}\nentries = []\n", VAULT_VERSION + 1);
+    assert!(matches!(
+        parse_vault(&newer),
+        Err(AuthError::UnsupportedVersion(v)) if v == VAULT_VERSION + 1
+    ));
+    assert!(matches!(
+        parse_vault("version = 0\nentries = []\n"),
+        Err(AuthError::ParseError)
+    ));
+    assert!(matches!(
+        parse_vault("version = \"3\"\nentries = []\n"),
+        Err(AuthError::ParseError)
+    ));
+    assert!(matches!(
+        parse_vault("entries = 1"),
+        Err(AuthError::ParseError)
+    ));
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use auth::auth_core::backup::Backups;
+use auth::auth_core::entry::Entry;
+use auth::auth_core::sort::{SortMode, sorted_indices};
+use auth::ui::list_view::ListView;
+use common::{TestApp, add, entry, names, press, press_in};
+use ratatui::crossterm::event::KeyCode;
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn test_paging_and_jumps() {
+    let mut app = TestApp::new("navigation");
+    app.entries = (0..10).map(|i| entry(&format!("entry {i}"))).collect();
+
+    // Page keys move by the height the list was last drawn with.
+    let list = ListView {
+        height: 4,
+        ..ListView::default()
+    };
+    press_in(&mut app, KeyCode::PageDown, &list);
+    assert_eq!(app.selected, 4);
+    press_in(&mut app, KeyCode::PageDown, &list);
+    press_in(&mut app, KeyCode::PageDown, &list);
+    assert_eq!(app.selected, 9);
+    press_in(&mut app, KeyCode::PageUp, &list);
+    assert_eq!(app.selected, 5);
+
+    press(&mut app, KeyCode::Char('g'));
+    press(&mut app, KeyCode::Char('g'));
+    assert_eq!(app.selected, 0);
+    press(&mut app, KeyCode::Char('G'));
+    assert_eq!(app.selected, 9);
+    press(&mut app, KeyCode::Home);
+    assert_eq!(app.selected, 0);
+    press(&mut app, KeyCode::End);
+    assert_eq!(app.selected, 9);
+}
+
+#[test]
+fn test_sort_modes() {
+    let entry = |name: &str, last_used, use_count| Entry {
+        name: name.to_string(),
+        last_used,
+        use_count,
+        ..Entry::default()
+    };
+    let entries = vec![entry("b", 30, 1), entry("a", 10, 5), entry("c", 20, 3)];
+
+    assert_eq!(sorted_indices(&entries, SortMode::Manual), vec![0, 1, 2]);
+    assert_eq!(sorted_indices(&entries, SortMode::Name), vec![1, 0, 2]);
+    assert_eq!(sorted_indices(&entries, SortMode::Recent), vec![0, 2, 1]);
+    assert_eq!(sorted_indices(&entries, SortMode::Frequent), vec![1, 2, 0]);
+}
+
+#[test]
+fn test_sort_by_issuer() {
+    let entry = |name: &str, issuer: &str, account: &str| Entry {
+        name: name.to_string(),
+        issuer: issuer.to_string(),
+        account: account.to_string(),
+        ..Entry::default()
+    };
+    let entries = vec![
+        entry("a", "", ""),
+        entry("b", "GitHub", "work"),
+        entry("c", "AWS", ""),
+        entry("d", "github", "home"),
+    ];
+
+    assert_eq!(sorted_indices(&entries, SortMode::Issuer), vec![2, 3, 1, 0]);
+    // Names sort by the title the list shows, i.e. the issuer where there is one.
+    assert_eq!(sorted_indices(&entries, SortMode::Name), vec![0, 2, 1, 3]);
+}
+
+#[test]
+#[serial]
+fn test_reorder_persists() {
+    let mut app = TestApp::new("reorder");
+    for name in ["first", "second"] {
+        add(&mut app, name);
+    }
+    press(&mut app, KeyCode::Char('J'));
+    assert_eq!(app.selected, 1);
+    press(&mut app, KeyCode::Char('o'));
+    assert!(app.sort == SortMode::Name);
+
+    let reloaded = app.open_again();
+    assert_eq!(names(&reloaded), ["second", "first"]);
+    assert!(reloaded.sort == SortMode::Name);
+}
+
+#[test]
+#[serial]
+fn test_sorting_takes_no_snapshots() {
+    let mut app = TestApp::new("sort_snapshots");
+    for name in ["first", "second"] {
+        add(&mut app, name);
+    }
+    let backups = Backups::new(app.dir(), 10);
+    let snapshots = backups.list().len();
+
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Char('o'));
+    }
+    assert!(app.sort == SortMode::Manual);
+    assert_eq!(backups.list().len(), snapshots);
+}
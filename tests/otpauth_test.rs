@@ -0,0 +1,61 @@
+mod common;
+
+use auth::AuthError;
+use auth::auth_core::otpauth::{is_otpauth_uri, parse_otpauth};
+use common::{SECRET, TestApp};
+use serial_test::serial;
+
+#[test]
+fn test_parse_otpauth_uri() {
+    let parsed = parse_otpauth(
+        "otpauth://totp/ACME%20Co:john.doe%40email.com?secret=JBSWY3DPEHPK3PXP&issuer=ACME%20Co",
+    )
+    .unwrap();
+    assert_eq!(*parsed.secret, "JBSWY3DPEHPK3PXP");
+    assert_eq!(parsed.issuer, "ACME Co");
+    assert_eq!(parsed.account, "john.doe@email.com");
+
+    let parsed = parse_otpauth("otpauth://totp/GitHub:octocat?secret=JBSWY3DPEHPK3PXP").unwrap();
+    assert_eq!(parsed.issuer, "GitHub");
+    assert_eq!(parsed.account, "octocat");
+
+    let parsed = parse_otpauth("otpauth://totp/octocat?secret=JBSWY3DPEHPK3PXP").unwrap();
+    assert_eq!(parsed.issuer, "");
+    assert_eq!(parsed.account, "octocat");
+}
+
+#[test]
+fn test_invalid_otpauth_uri() {
+    assert!(!is_otpauth_uri("JBSWY3DPEHPK3PXP"));
+    assert!(parse_otpauth("otpauth://hotp/GitHub:octocat?secret=JBSWY3DPEHPK3PXP").is_err());
+    assert!(parse_otpauth("otpauth://totp/GitHub:octocat?issuer=GitHub").is_err());
+    assert!(parse_otpauth("otpauth://totp/GitHub:octocat?secret=%FF").is_err());
+}
+
+#[test]
+fn test_unsupported_otpauth_settings() {
+    let base = "otpauth://totp/GitHub:octocat?secret=JBSWY3DPEHPK3PXP";
+    assert!(parse_otpauth(&format!("{base}&algorithm=SHA1&digits=6&period=30")).is_ok());
+    for params in ["algorithm=SHA256", "digits=8", "period=60", "digits=six"] {
+        assert!(matches!(
+            parse_otpauth(&format!("{base}&{params}")),
+            Err(AuthError::UnsupportedTotpError)
+        ));
+    }
+}
+
+#[test]
+#[serial]
+fn test_add_entry_from_otpauth_uri() {
+    let mut app = TestApp::new("add_otpauth");
+    app.new_entry_secret = "otpauth://totp/GitHub:octocat?secret=JBSWY3DPEHPK3PXP"
+        .to_string()
+        .into();
+    app.add_entry();
+
+    assert_eq!(app.entries.len(), 1);
+    assert_eq!(app.entries[0].name, "GitHub");
+    assert_eq!(app.entries[0].issuer, "GitHub");
+    assert_eq!(app.entries[0].account, "octocat");
+    assert_eq!(*app.entries[0].secret, SECRET);
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use std::thread;
+use std::time::Duration;
+
+use common::{TestApp, add, press};
+use ratatui::crossterm::event::KeyCode;
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn test_privacy_masks_and_reveals_codes() {
+    let mut app = TestApp::with_config("privacy", "[privacy]\nenabled = true\nreveal_secs = 1\n");
+    for name in ["first", "second"] {
+        add(&mut app, name);
+    }
+    app.selected = 0;
+    assert!(!app.is_code_visible(0) && !app.is_code_visible(1));
+
+    // Only the selected code is revealed, and only while it stays selected.
+    press(&mut app, KeyCode::Char('v'));
+    assert!(app.is_code_visible(0) && !app.is_code_visible(1));
+    press(&mut app, KeyCode::Char('j'));
+    assert!(!app.is_code_visible(0) && !app.is_code_visible(1));
+
+    press(&mut app, KeyCode::Char('v'));
+    assert!(app.is_code_visible(1));
+    thread::sleep(Duration::from_secs(1));
+    assert!(!app.is_code_visible(1));
+
+    press(&mut app, KeyCode::Char('p'));
+    assert!(app.is_code_visible(0) && app.is_code_visible(1));
+    press(&mut app, KeyCode::Char('v'));
+    press(&mut app, KeyCode::Char('p'));
+    assert!(!app.is_code_visible(1));
+}
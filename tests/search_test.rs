@@ -0,0 +1,39 @@
+use auth::auth_core::entry::Entry;
+use auth::auth_core::search::{fuzzy_match, search};
+
+fn entry(name: &str) -> Entry {
+    Entry {
+        name: name.to_string(),
+        secret: "JBSWY3DPEHPK3PXP".to_string().into(),
+        ..Entry::default()
+    }
+}
+
+#[test]
+fn test_fuzzy_match_positions() {
+    let (positions, _) = fuzzy_match("ghb", "GitHub").unwrap();
+    assert_eq!(positions, vec![0, 3, 5]);
+
+    assert!(fuzzy_match("hg", "GitHub").is_none());
+    assert!(fuzzy_match("", "GitHub").unwrap().0.is_empty());
+}
+
+#[test]
+fn test_search_ranks_tighter_matches_first() {
+    let entries = vec![entry("Gandi Mail"), entry("Gmail"), entry("Amazon")];
+
+    let matches = search(&entries, &[0, 1, 2], "mail");
+    let indices: Vec<usize> = matches.iter().map(|m| m.index).collect();
+    assert_eq!(indices, vec![1, 0]);
+}
+
+#[test]
+fn test_empty_query_keeps_given_order() {
+    let entries = vec![entry("b"), entry("a")];
+
+    let indices: Vec<usize> = search(&entries, &[1, 0], "")
+        .iter()
+        .map(|m| m.index)
+        .collect();
+    assert_eq!(indices, vec![1, 0]);
+}
@@ -0,0 +1,199 @@
+mod common;
+
+use std::thread;
+
+use auth::AuthError;
+use auth::auth_core::app::App;
+use auth::auth_core::entry::Entry;
+use auth::auth_core::sync::{WATCH_INTERVAL, merge};
+use common::{TestApp, add, entry, names, press};
+use ratatui::crossterm::event::KeyCode;
+use serial_test::serial;
+
+/// Entry with a fixed id, so the same entry can be spelled out on every side of a merge.
+fn with_id(id: u64, name: &str) -> Entry {
+    Entry { id, ..entry(name) }
+}
+
+fn merged_names(base: &[Entry], local: &[Entry], disk: &[Entry]) -> Vec<String> {
+    merge(base, local, disk)
+        .into_iter()
+        .map(|e| e.name)
+        .collect()
+}
+
+#[test]
+fn test_merge_keeps_both_additions() {
+    let base = vec![with_id(1, "a"), with_id(2, "b")];
+    let local = vec![with_id(1, "a"), with_id(2, "b"), with_id(3, "local")];
+    let disk = vec![with_id(1, "a"), with_id(2, "b"), with_id(4, "remote")];
+
+    assert_eq!(
+        merged_names(&base, &local, &disk),
+        vec!["a", "b", "local", "remote"]
+    );
+}
+
+#[test]
+fn test_merge_applies_both_removals() {
+    let base = vec![with_id(1, "a"), with_id(2, "b"), with_id(3, "c")];
+    let local = vec![with_id(1, "a"), with_id(3, "c")];
+    let disk = vec![with_id(1, "a"), with_id(2, "b")];
+
+    assert_eq!(merged_names(&base, &local, &disk), vec!["a"]);
+}
+
+#[test]
+fn test_merge_edit_stays_in_place() {
+    let base = vec![with_id(1, "a"), with_id(2, "b")];
+    let local = vec![with_id(1, "a"), with_id(2, "b2")];
+    let disk = vec![with_id(1, "a"), with_id(2, "b"), with_id(3, "remote")];
+
+    assert_eq!(
+        merged_names(&base, &local, &disk),
+        vec!["a", "b2", "remote"]
+    );
+}
+
+#[test]
+fn test_merge_edit_survives_removal() {
+    let base = vec![with_id(1, "a")];
+    let local = vec![with_id(1, "a2")];
+
+    assert_eq!(merged_names(&base, &local, &[]), vec!["a2"]);
+    assert_eq!(merged_names(&base, &[], &local), vec!["a2"]);
+}
+
+#[test]
+fn test_merge_without_local_changes_reloads() {
+    let base = vec![with_id(1, "a"), with_id(2, "b")];
+    let disk = vec![with_id(2, "b"), with_id(3, "c")];
+
+    assert_eq!(merged_names(&base, &base, &disk), vec!["b", "c"]);
+}
+
+#[test]
+fn test_merge_keeps_local_order() {
+    let base = vec![with_id(1, "a"), with_id(2, "b"), with_id(3, "c")];
+    let local = vec![with_id(2, "b"), with_id(1, "a"), with_id(3, "c")];
+    let disk = vec![
+        with_id(1, "a"),
+        with_id(2, "b"),
+        with_id(3, "c"),
+        with_id(4, "d"),
+    ];
+
+    assert_eq!(merged_names(&base, &local, &disk), vec!["b", "a", "c", "d"]);
+}
+
+#[test]
+fn test_merge_adds_up_usage() {
+    let used = |count| Entry {
+        use_count: count,
+        last_used: count * 10,
+        ..with_id(1, "a")
+    };
+    let merged = merge(&[used(1)], &[used(3)], &[used(2)]);
+
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].use_count, 4);
+    assert_eq!(merged[0].last_used, 30);
+}
+
+#[test]
+#[serial]
+fn test_instances_merge_and_reload() {
+    let mut first = TestApp::new("sync_instances");
+    let mut second = first.open_again();
+
+    add(&mut first, "first");
+    add(&mut second, "second");
+    assert_eq!(names(&second), vec!["first", "second"]);
+
+    thread::sleep(WATCH_INTERVAL);
+    first.tick();
+    assert_eq!(names(&first), vec!["first", "second"]);
+
+    first.entries[0].use_count += 1;
+    first.save_entries().unwrap();
+    second.entries[0].use_count += 1;
+    second.save_entries().unwrap();
+    assert_eq!(names(&second), vec!["first", "second"]);
+    assert_eq!(second.entries[0].use_count, 2);
+}
+
+#[test]
+#[serial]
+fn test_edit_survives_reload() {
+    let mut first = TestApp::new("sync_edit");
+    for name in ["a", "b"] {
+        add(&mut first, name);
+    }
+    first.selected = 1;
+    press(&mut first, KeyCode::Char('E'));
+
+    // Another instance reorders the list while the edit popup is open.
+    let mut second = first.open_again();
+    second.entries.swap(0, 1);
+    second.save_entries().unwrap();
+    thread::sleep(WATCH_INTERVAL);
+    first.tick();
+    assert_eq!(names(&first), vec!["a", "b"]);
+
+    first.edit_entry_name = "renamed".to_string();
+    press(&mut first, KeyCode::Enter);
+    press(&mut first, KeyCode::Enter);
+    assert_eq!(names(&first), vec!["renamed", "a"]);
+}
+
+#[test]
+#[serial]
+fn test_startup_reports_config_and_load_errors() {
+    let app = TestApp::new("startup_errors");
+    std::fs::write(app.dir().join("config.toml"), "retention = ").unwrap();
+    std::fs::write(app.dir().join("entries.toml"), "not a vault").unwrap();
+
+    let reopened = app.open_again();
+    let (message, _) = reopened.error_message.as_ref().unwrap();
+    assert!(message.contains(&AuthError::ReadError.to_string()));
+    assert!(message.contains(&AuthError::ConfigError.to_string()));
+}
+
+fn notification(app: &App) -> Option<&str> {
+    app.notification
+        .as_ref()
+        .map(|(message, _)| message.as_str())
+}
+
+#[test]
+#[serial]
+fn test_external_change_notifications() {
+    let mut first = TestApp::new("sync_notify");
+    let mut second = first.open_again();
+
+    add(&mut first, "a");
+    thread::sleep(WATCH_INTERVAL);
+    second.tick();
+    assert_eq!(
+        notification(&second),
+        Some("Vault changed on disk: 1 added")
+    );
+
+    // Usage counts saved by the other instance are merged silently.
+    second.notification = None;
+    first.entries[0].use_count += 1;
+    first.save_entries().unwrap();
+    thread::sleep(WATCH_INTERVAL);
+    second.tick();
+    assert_eq!(second.entries[0].use_count, 1);
+    assert!(second.notification.is_none());
+
+    first.entries[0].name = "renamed".to_string();
+    first.save_entries().unwrap();
+    thread::sleep(WATCH_INTERVAL);
+    second.tick();
+    assert_eq!(
+        notification(&second),
+        Some("Vault changed on disk: 1 edited")
+    );
+}
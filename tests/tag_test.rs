@@ -0,0 +1,115 @@
+mod common;
+
+use auth::auth_core::app::App;
+use auth::auth_core::entry::Entry;
+use auth::auth_core::search::search;
+use auth::auth_core::tag::{collect_tags, format_tags, parse_tags};
+use common::{TestApp, entry, names, press};
+use ratatui::crossterm::event::KeyCode;
+use serial_test::serial;
+
+fn tagged(name: &str, tags: &[&str]) -> Entry {
+    Entry {
+        tags: tags.iter().map(ToString::to_string).collect(),
+        ..entry(name)
+    }
+}
+
+#[test]
+fn test_parse_and_format_tags() {
+    let tags = parse_tags(" work, personal,,Work , ");
+    assert_eq!(tags, vec!["work", "personal"]);
+    assert_eq!(format_tags(&tags), "work, personal");
+    assert!(parse_tags("").is_empty());
+}
+
+#[test]
+fn test_collect_tags() {
+    let entries = vec![
+        tagged("a", &["work", "customer"]),
+        tagged("b", &["Personal"]),
+        tagged("c", &["Work"]),
+        tagged("d", &[]),
+    ];
+    assert_eq!(collect_tags(&entries), vec!["customer", "Personal", "work"]);
+}
+
+#[test]
+fn test_search_tag_filters() {
+    let entries = vec![
+        tagged("GitHub", &["work"]),
+        tagged("GitLab", &["personal"]),
+        tagged("Gmail", &["personal", "work"]),
+    ];
+    let order = [0, 1, 2];
+    let indices = |query: &str| -> Vec<usize> {
+        let mut indices: Vec<usize> = search(&entries, &order, query)
+            .iter()
+            .map(|m| m.index)
+            .collect();
+        indices.sort_unstable();
+        indices
+    };
+
+    assert_eq!(indices("#work"), vec![0, 2]);
+    assert_eq!(indices("#WO"), vec![0, 2]);
+    assert_eq!(indices("git #work"), vec![0]);
+    assert_eq!(indices("#work #personal"), vec![2]);
+    assert_eq!(indices("#"), vec![0, 1, 2]);
+    assert!(indices("#customer").is_empty());
+}
+
+#[test]
+#[serial]
+fn test_tag_tabs() {
+    let mut app = TestApp::new("tag_tabs");
+    app.entries = vec![
+        tagged("GitHub", &["work"]),
+        tagged("Bank", &["personal"]),
+        tagged("Email", &[]),
+    ];
+    let visible =
+        |app: &App| -> Vec<usize> { app.visible_entries().iter().map(|m| m.index).collect() };
+
+    assert_eq!(visible(&app), vec![0, 1, 2]);
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.active_tag(), Some("personal"));
+    assert_eq!(visible(&app), vec![1]);
+    assert_eq!(app.selected, 1);
+
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(visible(&app), vec![0]);
+    press(&mut app, KeyCode::Char('a'));
+    assert_eq!(app.new_entry_tags, "work");
+    press(&mut app, KeyCode::Esc);
+
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.active_tag(), None);
+    press(&mut app, KeyCode::BackTab);
+    assert_eq!(app.active_tag(), Some("work"));
+
+    app.entries[0].tags.clear();
+    assert_eq!(app.active_tag(), None);
+    assert_eq!(visible(&app), vec![0, 1, 2]);
+}
+
+#[test]
+#[serial]
+fn test_move_within_tag_tab() {
+    let mut app = TestApp::new("tag_move");
+    app.entries = vec![
+        tagged("GitHub", &["work"]),
+        tagged("Bank", &["personal"]),
+        tagged("GitLab", &["work"]),
+    ];
+    app.tag_filter = Some("work".to_string());
+    app.selected = 0;
+
+    // The neighbour in the tab is GitLab, not the hidden Bank entry in between.
+    app.move_entry(1);
+    assert_eq!(names(&app), ["GitLab", "Bank", "GitHub"]);
+    assert_eq!(app.selected, 2);
+
+    app.move_entry(1);
+    assert_eq!(app.selected, 2);
+}
@@ -1,4 +1,4 @@
-use auth::auth_core::totp::generate_totp;
+use auth::auth_core::totp::{generate_totp, generate_totp_at};
 
 #[test]
 fn test_valid_totp_generation() {
@@ -42,3 +42,16 @@ fn test_normalized_secret() {
     assert_eq!(code.len(), 6);
     assert!(remaining <= 30);
 }
+
+#[test]
+fn test_totp_at_period_boundaries() {
+    let (first, remaining) = generate_totp_at("JBSWY3DPEHPK3PXP", 59).unwrap();
+    assert_eq!(remaining, 1);
+
+    let (next, remaining) = generate_totp_at("JBSWY3DPEHPK3PXP", 60).unwrap();
+    assert_eq!(remaining, 30);
+    assert_ne!(first, next);
+
+    let (same, _) = generate_totp_at("JBSWY3DPEHPK3PXP", 89).unwrap();
+    assert_eq!(next, same);
+}
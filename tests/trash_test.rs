@@ -0,0 +1,73 @@
+mod common;
+
+use common::{TestApp, add, names};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn test_trash_restore_and_expiry() {
+    let mut app = TestApp::new("trash");
+    for name in ["first", "second"] {
+        add(&mut app, name);
+    }
+    app.selected = 0;
+    app.delete_entry();
+    assert_eq!(app.entries.len(), 1);
+
+    let mut reloaded = app.open_again();
+    assert_eq!(reloaded.trash.len(), 1);
+    assert_eq!(reloaded.trash[0].entry.name, "first");
+
+    reloaded.restore_trashed_entry();
+    assert!(reloaded.trash.is_empty());
+    assert_eq!(reloaded.entries.len(), 2);
+    assert_eq!(reloaded.entries[1].name, "first");
+
+    reloaded.delete_all_entries();
+    assert_eq!(reloaded.trash.len(), 2);
+    reloaded.trash[0].deleted_at = 0;
+    reloaded.save_entries().unwrap();
+    assert_eq!(reloaded.trash.len(), 1);
+}
+
+#[test]
+#[serial]
+fn test_restoring_a_backup_restores_its_trash() {
+    let mut app = TestApp::new("trash_backup");
+    for name in ["first", "second"] {
+        add(&mut app, name);
+    }
+    app.selected = 0;
+    app.delete_entry();
+
+    // The newest snapshot is from before the delete, when "first" was not in the trash yet.
+    app.open_restore_view();
+    app.restore_selected_snapshot();
+    assert_eq!(names(&app), ["first", "second"]);
+    assert!(app.trash.is_empty());
+
+    // So there is nothing left to restore a second copy of "first" from.
+    app.restore_trashed_entry();
+    assert_eq!(names(&app), ["first", "second"]);
+
+    app.undo();
+    assert_eq!(names(&app), ["second"]);
+    assert_eq!(app.trash[0].entry.name, "first");
+}
+
+#[test]
+#[serial]
+fn test_huge_trash_retention_keeps_entries() {
+    let app = TestApp::new("trash_retention");
+    std::fs::write(
+        app.dir().join("config.toml"),
+        format!("[trash]\nretention_days = {}\n", i64::MAX),
+    )
+    .unwrap();
+
+    let mut reopened = app.open_again();
+    assert!(reopened.error_message.is_none());
+    add(&mut reopened, "entry");
+    reopened.delete_entry();
+    assert_eq!(reopened.trash.len(), 1);
+}